@@ -0,0 +1,251 @@
+//! Procedural macros backing the [`assert_matches_regex`] crate. This is an
+//! implementation detail; use the macros re-exported from that crate instead.
+//!
+//! [`assert_matches_regex`]: https://docs.rs/assert_matches_regex
+
+#![warn(missing_docs)]
+#![warn(rust_2018_idioms)]
+
+use proc_macro2::{Delimiter, Group, Literal, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+
+/// Checks that a string literal pattern is a valid regex, emitting a
+/// `compile_error!` at the literal if it is not. Any other expression expands
+/// to nothing and is left to be checked at runtime.
+///
+/// A leading `bytes` checks the pattern as a `regex::bytes::Regex` would,
+/// which allows matching invalid UTF-8 with `(?-u)`. With the `regex-lite`
+/// feature, the pattern is checked by compiling it with `regex-lite` instead.
+/// A leading `fancy` checks the pattern by compiling it with `fancy-regex`.
+#[proc_macro]
+pub fn validate_regex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    validate(input.into()).into()
+}
+
+fn validate(input: TokenStream) -> TokenStream {
+    let mut iter = input.into_iter().peekable();
+    let kind = match iter.peek() {
+        Some(TokenTree::Ident(ident)) if ident == "bytes" || ident == "fancy" => {
+            let kind = ident.to_string();
+            iter.next();
+            kind
+        }
+        _ => String::new(),
+    };
+    let lit = match string_literal(iter.collect()) {
+        Some(lit) => lit,
+        None => return TokenStream::new(),
+    };
+    let result = match kind.as_str() {
+        "fancy" => parse_fancy(&lit.value()),
+        kind => parse(&lit.value(), kind == "bytes"),
+    };
+    match result {
+        Ok(()) => TokenStream::new(),
+        Err(msg) => quote_spanned!(lit.span()=> ::core::compile_error!(#msg);),
+    }
+}
+
+/// Parses `pattern` as the regex engine would, returning the error message if
+/// it is invalid.
+#[cfg(feature = "regex")]
+fn parse(pattern: &str, bytes: bool) -> Result<(), String> {
+    let mut parser = regex_syntax::ParserBuilder::new().utf8(!bytes).build();
+    parser
+        .parse(pattern)
+        .map(drop)
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(all(feature = "regex-lite", not(feature = "regex")))]
+fn parse(pattern: &str, _bytes: bool) -> Result<(), String> {
+    // regex-lite has no parser of its own to call, so compile the pattern.
+    regex_lite::Regex::new(pattern)
+        .map(drop)
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(not(any(feature = "regex", feature = "regex-lite")))]
+fn parse(_pattern: &str, _bytes: bool) -> Result<(), String> {
+    Ok(())
+}
+
+/// Compiles `pattern` with fancy-regex, returning the error message if it is
+/// invalid.
+#[cfg(feature = "fancy-regex")]
+fn parse_fancy(pattern: &str) -> Result<(), String> {
+    fancy_regex::Regex::new(pattern)
+        .map(drop)
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(not(feature = "fancy-regex"))]
+fn parse_fancy(_pattern: &str) -> Result<(), String> {
+    Ok(())
+}
+
+/// Expands a pattern written as an array of fragments into a single
+/// verbose-mode pattern, with `(?x)` on the first line and one fragment per
+/// line after it, and passes it back to `__regex!`. Arrays of string literals
+/// become a string literal, so they are still validated and cached; any
+/// other pattern is passed back as is, to be compiled at runtime.
+///
+/// The input is `$crate; <type>; <pattern>`, where `<type>` is a regex type,
+/// `bytes`, or `fancy`.
+#[proc_macro]
+pub fn regex_fragments(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    fragments(input.into()).into()
+}
+
+fn fragments(input: TokenStream) -> TokenStream {
+    let mut parts = split(input, ';', 3).into_iter();
+    let (krate, ty, re) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(krate), Some(ty), Some(re)) => (krate, ty, re),
+        _ => {
+            return quote!(::core::compile_error!(
+                "expected `$crate; <type>; <pattern>`"
+            ))
+        }
+    };
+    let group = match array(re.clone()) {
+        Some(group) => group,
+        None => return quote!(#krate::__regex!(@compile #ty; #re)),
+    };
+    let lits: Option<Vec<_>> = split(group.stream(), ',', usize::MAX)
+        .into_iter()
+        .filter(|fragment| !fragment.is_empty())
+        .map(string_literal)
+        .collect();
+    match lits {
+        Some(lits) => {
+            let mut pattern = "(?x)".to_owned();
+            for lit in &lits {
+                pattern.push('\n');
+                pattern.push_str(&lit.value());
+            }
+            let mut lit = Literal::string(&pattern);
+            lit.set_span(group.span());
+            quote!(#krate::__regex!(#ty; #lit))
+        }
+        None => quote!(#krate::__regex!(@compile #ty; #krate::__private::verbose(&#group))),
+    }
+}
+
+/// Splits `input` at the top-level `sep` punctuation, into at most `limit`
+/// parts.
+fn split(input: TokenStream, sep: char, limit: usize) -> Vec<TokenStream> {
+    let mut parts = vec![TokenStream::new()];
+    for tt in input {
+        match &tt {
+            TokenTree::Punct(punct) if punct.as_char() == sep && parts.len() < limit => {
+                parts.push(TokenStream::new());
+            }
+            _ => parts.last_mut().unwrap().extend([tt]),
+        }
+    }
+    parts
+}
+
+/// Returns the bracketed group that makes up all of `input`, looking through
+/// invisible groups.
+fn array(input: TokenStream) -> Option<Group> {
+    let mut iter = input.into_iter();
+    let tt = iter.next()?;
+    if iter.next().is_some() {
+        return None;
+    }
+    match tt {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::None => array(group.stream()),
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Bracket => Some(group),
+        _ => None,
+    }
+}
+
+/// Returns the string literal that makes up all of `input`, looking through
+/// the invisible groups that wrap `$re:expr` fragments.
+fn string_literal(input: TokenStream) -> Option<syn::LitStr> {
+    let mut iter = input.into_iter();
+    let tt = iter.next()?;
+    if iter.next().is_some() {
+        return None;
+    }
+    match tt {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+            string_literal(group.stream())
+        }
+        TokenTree::Literal(lit) => syn::parse2(TokenTree::Literal(lit).into()).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fragments, validate};
+    use proc_macro2::TokenStream;
+    use quote::quote;
+
+    fn validate_str(input: TokenStream) -> String {
+        validate(input).to_string()
+    }
+
+    #[test]
+    fn valid_literal() {
+        assert_eq!(validate_str(quote!(r"\w+")), "");
+        assert_eq!(validate_str(quote!("^[a-f0-9]+$")), "");
+    }
+
+    #[test]
+    fn non_literal() {
+        assert_eq!(validate_str(quote!(pattern)), "");
+        assert_eq!(validate_str(quote!(String::from("[a-z"))), "");
+        assert_eq!(validate_str(quote!(42)), "");
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn bytes_literal() {
+        assert_eq!(validate_str(quote!(bytes r"(?-u)\xFF")), "");
+        let output = validate_str(quote!(r"(?-u)\xFF"));
+        assert!(output.contains("invalid UTF-8"), "{output}");
+    }
+
+    #[test]
+    #[cfg(feature = "fancy-regex")]
+    fn fancy_literal() {
+        assert_eq!(validate_str(quote!(fancy r"foo(?!bar)")), "");
+        assert_eq!(validate_str(quote!(fancy r"<(\w+)>.*</\1>")), "");
+        let output = validate_str(quote!(fancy r"(a)\2"));
+        assert!(output.contains("Invalid back reference"), "{output}");
+    }
+
+    #[test]
+    fn invalid_literal() {
+        let output = validate_str(quote!(r"[a-z"));
+        assert!(output.starts_with(":: core :: compile_error !"), "{output}");
+        assert!(output.contains("unclosed"), "{output}");
+    }
+
+    #[test]
+    fn fragments_literals() {
+        let output = fragments(quote!(krate; Ty; [r"^(?<key>\w+)", r"=  # separator",]));
+        assert_eq!(
+            output.to_string(),
+            quote!(krate::__regex!(Ty; "(?x)\n^(?<key>\\w+)\n=  # separator")).to_string(),
+        );
+    }
+
+    #[test]
+    fn fragments_non_literals() {
+        let output = fragments(quote!(krate; Ty; [key, "="]));
+        assert_eq!(
+            output.to_string(),
+            quote!(krate::__regex!(@compile Ty; krate::__private::verbose(&[key, "="])))
+                .to_string(),
+        );
+        let output = fragments(quote!(krate; bytes; pattern));
+        assert_eq!(
+            output.to_string(),
+            quote!(krate::__regex!(@compile bytes; pattern)).to_string(),
+        );
+    }
+}
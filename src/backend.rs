@@ -0,0 +1,17 @@
+//! The regex engine behind the assertion macros: either `regex` or
+//! `regex-lite`, chosen by the cargo feature of the same name.
+
+#[cfg(all(feature = "regex", feature = "regex-lite"))]
+compile_error!(
+    "the `regex` and `regex-lite` features are mutually exclusive; \
+     turn off default features to use `regex-lite`"
+);
+
+#[cfg(not(any(feature = "regex", feature = "regex-lite")))]
+compile_error!("either the `regex` or the `regex-lite` feature must be enabled");
+
+#[cfg(feature = "regex")]
+pub use regex::{escape, Captures, Error, Regex, RegexBuilder};
+
+#[cfg(all(feature = "regex-lite", not(feature = "regex")))]
+pub use regex_lite::{escape, Captures, Error, Regex, RegexBuilder};
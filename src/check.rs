@@ -0,0 +1,393 @@
+//! Non-panicking checks, which the assertion macros are built on.
+
+use crate::backend::{Captures, Error as RegexError, Regex};
+use crate::compile::{CompileError, Compiled};
+use crate::haystack::{LossyNote, Text};
+use crate::options::Options;
+use crate::render::{self, HaystackName, Mismatch, Pattern, PatternLines, WithOptions};
+use std::error::Error;
+use std::fmt;
+use std::panic::Location;
+
+/// Checks whether `haystack` matches the regex `pattern`.
+///
+/// This runs the same matching as [`assert_matches_regex!`], but returns
+/// the failure instead of panicking, which is useful for test harnesses that
+/// collect failures or for tests that return a `Result`.
+///
+/// [`assert_matches_regex!`]: macro.assert_matches_regex.html
+///
+/// # Examples
+///
+/// ```
+/// use assert_matches_regex::{check_matches_regex, MatchErrorKind};
+///
+/// assert!(check_matches_regex("Hello!", r"(?i)hello").is_ok());
+///
+/// let err = check_matches_regex("Hello!", r"\d").unwrap_err();
+/// assert!(matches!(err.kind(), MatchErrorKind::NoMatch));
+/// assert_eq!(err.to_string(), r#"`"Hello!"` does not match `\d`"#);
+///
+/// let err = check_matches_regex("Hello!", r"[a-z").unwrap_err();
+/// assert!(matches!(err.kind(), MatchErrorKind::InvalidRegex(_)));
+/// ```
+pub fn check_matches_regex(haystack: &str, pattern: &str) -> Result<(), MatchError> {
+    check(haystack.into(), &Compiled::new(pattern))
+}
+
+/// Like [`check_matches_regex`], with a pattern compiled by the caller.
+pub fn check(haystack: Text<'_>, re: &Compiled<Regex>) -> Result<(), MatchError> {
+    check_captures(haystack, re).map(drop)
+}
+
+/// Like [`check`], but returns the captures of the leftmost match.
+pub fn check_captures<'h>(
+    haystack: Text<'h>,
+    re: &Compiled<Regex>,
+) -> Result<Captures<'h>, MatchError> {
+    let regex = match re.result() {
+        Ok(regex) => regex,
+        Err(err) => {
+            let kind = match err {
+                CompileError::Regex(err) => MatchErrorKind::InvalidRegex(err.clone()),
+                CompileError::Options => MatchErrorKind::OptionsOnCompiledRegex,
+                #[cfg(feature = "fancy-regex")]
+                CompileError::Fancy(_) | CompileError::Unsupported(_) => {
+                    unreachable!("`{}` was not compiled with fancy-regex", re.pattern())
+                }
+            };
+            return Err(MatchError::new(haystack, re, kind));
+        }
+    };
+    regex
+        .captures(haystack.as_str())
+        .ok_or_else(|| MatchError::new(haystack, re, MatchErrorKind::NoMatch))
+}
+
+/// The error returned by [`check_matches_regex`].
+///
+/// Its `Display` output is the same message that [`assert_matches_regex!`]
+/// would have panicked with, minus the leading "assertion failed: " and
+/// without color.
+///
+/// [`assert_matches_regex!`]: macro.assert_matches_regex.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchError {
+    haystack: String,
+    lossy: bool,
+    pattern: String,
+    options: Options,
+    kind: MatchErrorKind,
+}
+
+/// Why a [`MatchError`] occurred.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum MatchErrorKind {
+    /// The pattern is not a valid regex.
+    InvalidRegex(RegexError),
+    /// Options were given along with a regex that was already compiled, which
+    /// can't have them applied.
+    OptionsOnCompiledRegex,
+    /// The pattern is a valid regex, but it does not match the haystack.
+    NoMatch,
+}
+
+impl MatchError {
+    fn new<R>(haystack: Text<'_>, re: &Compiled<R>, kind: MatchErrorKind) -> Self {
+        MatchError {
+            haystack: haystack.to_string(),
+            lossy: haystack.is_lossy(),
+            pattern: re.pattern().to_owned(),
+            options: re.options().clone(),
+            kind,
+        }
+    }
+
+    /// The string that was checked.
+    pub fn haystack(&self) -> &str {
+        &self.haystack
+    }
+
+    /// The pattern that the haystack was checked against.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Why the check failed.
+    pub fn kind(&self) -> &MatchErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for MatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            MatchErrorKind::InvalidRegex(err) => {
+                write!(f, "`{}` is not a valid regex: {}", self.pattern, err)
+            }
+            MatchErrorKind::OptionsOnCompiledRegex => write!(
+                f,
+                "options `{}` can't be applied to `{}`, which is already compiled",
+                self.options, self.pattern,
+            ),
+            MatchErrorKind::NoMatch => {
+                let mismatch =
+                    Mismatch::with_color(&self.haystack, &self.pattern, &self.options, false);
+                write!(
+                    f,
+                    "{}{}{}",
+                    mismatch.summary(),
+                    mismatch.details(),
+                    LossyNote(self.lossy),
+                )
+            }
+        }
+    }
+}
+
+impl Error for MatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            MatchErrorKind::InvalidRegex(err) => Some(err),
+            MatchErrorKind::OptionsOnCompiledRegex | MatchErrorKind::NoMatch => None,
+        }
+    }
+}
+
+/// Panics with the assertion failure message for `err`, including the
+/// user-provided message, if any.
+#[track_caller]
+pub fn fail(macro_name: &str, err: &MatchError, args: Option<fmt::Arguments<'_>>) -> ! {
+    match &err.kind {
+        MatchErrorKind::InvalidRegex(regex_err) => {
+            panic_invalid_regex(macro_name, &err.pattern, &err.options, regex_err, args);
+        }
+        MatchErrorKind::OptionsOnCompiledRegex => {
+            panic_options_on_compiled(macro_name, &err.pattern, &err.options, args);
+        }
+        MatchErrorKind::NoMatch => {}
+    }
+    let mismatch = Mismatch::new(&err.haystack, &err.pattern, &err.options);
+    match args {
+        Some(args) => panic!(
+            "assertion failed: {}: {}{}{}",
+            mismatch.summary(),
+            args,
+            mismatch.details(),
+            LossyNote(err.lossy),
+        ),
+        None => panic!(
+            "assertion failed: {}{}{}",
+            mismatch.summary(),
+            mismatch.details(),
+            LossyNote(err.lossy),
+        ),
+    }
+}
+
+/// Panics because `re` failed to compile, or because it was already compiled
+/// and options were given along with it.
+#[track_caller]
+pub fn invalid_regex<R>(macro_name: &str, re: &Compiled<R>, args: Option<fmt::Arguments<'_>>) -> ! {
+    match re.result() {
+        Ok(_) => unreachable!("`{}` is a valid regex", re.pattern()),
+        Err(CompileError::Options) => {
+            panic_options_on_compiled(macro_name, re.pattern(), re.options(), args)
+        }
+        Err(err) => panic_invalid_regex(macro_name, re.pattern(), re.options(), err, args),
+    }
+}
+
+#[track_caller]
+fn panic_options_on_compiled(
+    macro_name: &str,
+    pattern: &str,
+    options: &Options,
+    args: Option<fmt::Arguments<'_>>,
+) -> ! {
+    let location = Location::caller();
+    let pattern = describe_pattern(pattern, options);
+    match args {
+        Some(args) => panic!(
+            "options can't be applied to an already compiled regex in {macro_name}! at {location}\n\
+             pattern:{pattern}\nmessage: {args}",
+        ),
+        None => panic!(
+            "options can't be applied to an already compiled regex in {macro_name}! at {location}\n\
+             pattern:{pattern}",
+        ),
+    }
+}
+
+#[track_caller]
+pub(crate) fn panic_invalid_regex(
+    macro_name: &str,
+    pattern: &str,
+    options: &Options,
+    err: &dyn fmt::Display,
+    args: Option<fmt::Arguments<'_>>,
+) -> ! {
+    let location = Location::caller();
+    let pattern = describe_pattern(pattern, options);
+    match args {
+        Some(args) => panic!(
+            "invalid regex in {macro_name}! at {location}\npattern:{pattern}\nmessage: {args}\n{err}",
+        ),
+        None => panic!("invalid regex in {macro_name}! at {location}\npattern:{pattern}\n{err}"),
+    }
+}
+
+/// The pattern after "pattern:" in a panic message, followed by the options,
+/// if any.
+fn describe_pattern(pattern: &str, options: &Options) -> String {
+    // A multi-line pattern goes on the lines below, as it was written.
+    let mut description = if render::is_multi_line(pattern) {
+        PatternLines(pattern).to_string()
+    } else {
+        format!(" `{pattern}`")
+    };
+    if !options.is_empty() {
+        description += &format!("\noptions: `{options}`");
+    }
+    description
+}
+
+/// Panics unless `re` matches `haystack`, for a regex that was translated from
+/// some other kind of pattern, such as a glob. The failure message shows both
+/// `source`, the pattern as written, and the regex.
+#[track_caller]
+pub(crate) fn assert_translated(
+    macro_name: &str,
+    kind: &str,
+    source: &str,
+    re: &Compiled<Regex>,
+    haystack: Text<'_>,
+    args: Option<fmt::Arguments<'_>>,
+) {
+    let regex = match re.result() {
+        Ok(regex) => regex,
+        Err(_) => invalid_regex(macro_name, re, args),
+    };
+    let note = haystack.note();
+    let haystack = haystack.as_str();
+    if regex.is_match(haystack) {
+        return;
+    }
+    // A multi-line source is shown line by line, like a multi-line pattern.
+    let summary = if render::is_multi_line(source) {
+        format!(
+            "{} does not match the {kind}{}",
+            HaystackName(haystack),
+            WithOptions(re.options()),
+        )
+    } else {
+        format!(
+            "{} does not match {kind} `{source}`{}",
+            HaystackName(haystack),
+            WithOptions(re.options()),
+        )
+    };
+    let mismatch = Mismatch::new(haystack, re.pattern(), re.options());
+    let details = format!(
+        "{}\nregex: `{}`{}",
+        PatternLines(source),
+        re.pattern(),
+        mismatch.details(),
+    );
+    match args {
+        Some(args) => panic!("assertion failed: {summary}: {args}{details}{note}"),
+        None => panic!("assertion failed: {summary}{details}{note}"),
+    }
+}
+
+/// Panics unless the capture group `name` matched exactly `expected`.
+#[track_caller]
+pub fn assert_capture(
+    haystack: Text<'_>,
+    re: &Regex,
+    caps: &Captures<'_>,
+    name: &str,
+    expected: &str,
+) {
+    let note = haystack.note();
+    let haystack = haystack.as_str();
+    if !re.capture_names().any(|n| n == Some(name)) {
+        panic!(
+            "assertion failed: `{haystack:?}` matches {} but it has no capture group named `{name}`{}{note}",
+            Pattern(re.as_str()),
+            PatternLines(re.as_str()),
+        );
+    }
+    match caps.name(name) {
+        Some(m) if m.as_str() == expected => {}
+        Some(m) => panic!(
+            "assertion failed: `{haystack:?}` matches {} but capture group `{name}` is `{:?}`, expected `{expected:?}`{}{note}",
+            Pattern(re.as_str()),
+            m.as_str(),
+            PatternLines(re.as_str()),
+        ),
+        None => panic!(
+            "assertion failed: `{haystack:?}` matches {} but capture group `{name}` did not participate, expected `{expected:?}`{}{note}",
+            Pattern(re.as_str()),
+            PatternLines(re.as_str()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_matches_regex, MatchErrorKind};
+    use std::error::Error;
+
+    #[test]
+    fn no_match() {
+        let err = check_matches_regex("abc", r"\d").unwrap_err();
+        assert_eq!(err.haystack(), "abc");
+        assert_eq!(err.pattern(), r"\d");
+        assert_eq!(err.kind(), &MatchErrorKind::NoMatch);
+        assert!(err.source().is_none());
+        assert_eq!(err.to_string(), r#"`"abc"` does not match `\d`"#);
+    }
+
+    #[test]
+    fn no_match_alternation() {
+        let err = check_matches_regex("xy!", r"abd|xyz").unwrap_err();
+        assert_eq!(err.to_string(), r#"`"xy!"` does not match `abd|xyz`"#);
+    }
+
+    #[test]
+    fn options_on_compiled_regex() {
+        let re = crate::backend::Regex::new("a").unwrap();
+        let compiled = crate::IntoAssertRegex::to_compiled(
+            &re,
+            crate::options::Options::new().case_insensitive(true),
+        );
+        let err = super::check("A".into(), &compiled).unwrap_err();
+        assert_eq!(err.kind(), &MatchErrorKind::OptionsOnCompiledRegex);
+        assert!(err.source().is_none());
+        assert_eq!(
+            err.to_string(),
+            "options `case_insensitive` can't be applied to `a`, which is already compiled",
+        );
+    }
+
+    #[test]
+    fn invalid_regex() {
+        let err = check_matches_regex("abc", r"[a-z").unwrap_err();
+        assert_eq!(err.pattern(), r"[a-z");
+        assert!(matches!(err.kind(), MatchErrorKind::InvalidRegex(_)));
+        assert!(err.source().is_some());
+        #[cfg(feature = "regex")]
+        assert_eq!(
+            err.to_string(),
+            "`[a-z` is not a valid regex: regex parse error:\n    [a-z\n    ^\n\
+             error: unclosed character class",
+        );
+        #[cfg(not(feature = "regex"))]
+        assert_eq!(
+            err.to_string(),
+            "`[a-z` is not a valid regex: found unclosed character class",
+        );
+    }
+}
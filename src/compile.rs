@@ -0,0 +1,330 @@
+//! Compiling patterns for the assertion macros.
+
+use crate::backend::{Error, Regex};
+use crate::options::Options;
+#[cfg(feature = "regex")]
+use regex::RegexSet;
+#[cfg(feature = "regex")]
+use regex_automata::{meta, Anchored, Input, MatchKind};
+use std::fmt;
+use std::sync::OnceLock;
+
+/// A regex type that the assertion macros know how to compile.
+pub trait FromPattern: Sized {
+    /// Compiles `pattern` with `options`.
+    fn from_pattern(pattern: &str, options: &Options) -> Result<Self, CompileError>;
+}
+
+impl FromPattern for Regex {
+    fn from_pattern(pattern: &str, options: &Options) -> Result<Self, CompileError> {
+        options.build(pattern).map_err(CompileError::Regex)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl FromPattern for regex::bytes::Regex {
+    fn from_pattern(pattern: &str, options: &Options) -> Result<Self, CompileError> {
+        options.build_bytes(pattern).map_err(CompileError::Regex)
+    }
+}
+
+#[cfg(feature = "fancy-regex")]
+impl FromPattern for fancy_regex::Regex {
+    fn from_pattern(pattern: &str, options: &Options) -> Result<Self, CompileError> {
+        options.build_fancy(pattern)
+    }
+}
+
+/// A regex for the assertion macros to match with: either a pattern to compile
+/// or an already compiled regex.
+///
+/// This is implemented for `str`, and so through auto-deref for `String`,
+/// `&str`, `Cow<str>`, and the like. It is also implemented for
+/// [`regex::Regex`] and [`regex::bytes::Regex`], and so for references to
+/// them and for statics such as `LazyLock<Regex>`. A compiled regex is used as
+/// it is, without being compiled again, so it can't be combined with options
+/// such as `case_insensitive`.
+///
+/// [`assert_full_match_regex!`], [`assert_matches_all_regex!`], and
+/// [`assert_matches_any_regex!`] only accept patterns, since they compile them
+/// into other kinds of regexes.
+///
+/// With the `regex-lite` feature, it is implemented for `regex_lite::Regex`
+/// instead of the `regex` types. With the `fancy-regex` feature, it is also
+/// implemented for `fancy_regex::Regex`.
+///
+/// [`regex::Regex`]: https://docs.rs/regex/*/regex/struct.Regex.html
+/// [`regex::bytes::Regex`]: https://docs.rs/regex/*/regex/bytes/struct.Regex.html
+/// [`assert_full_match_regex!`]: macro.assert_full_match_regex.html
+/// [`assert_matches_all_regex!`]: macro.assert_matches_all_regex.html
+/// [`assert_matches_any_regex!`]: macro.assert_matches_any_regex.html
+pub trait IntoAssertRegex<R> {
+    #[doc(hidden)]
+    fn to_compiled(&self, options: Options) -> Compiled<R>;
+}
+
+impl<R: FromPattern> IntoAssertRegex<R> for str {
+    fn to_compiled(&self, options: Options) -> Compiled<R> {
+        Compiled::with_options(self, options)
+    }
+}
+
+impl IntoAssertRegex<Regex> for Regex {
+    fn to_compiled(&self, options: Options) -> Compiled<Regex> {
+        Compiled::from_regex(self.as_str(), self.clone(), options)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl IntoAssertRegex<regex::bytes::Regex> for regex::bytes::Regex {
+    fn to_compiled(&self, options: Options) -> Compiled<regex::bytes::Regex> {
+        Compiled::from_regex(self.as_str(), self.clone(), options)
+    }
+}
+
+#[cfg(feature = "fancy-regex")]
+impl IntoAssertRegex<fancy_regex::Regex> for fancy_regex::Regex {
+    fn to_compiled(&self, options: Options) -> Compiled<fancy_regex::Regex> {
+        Compiled::from_regex(self.as_str(), self.clone(), options)
+    }
+}
+
+/// A pattern and its options, along with the result of compiling them.
+pub struct Compiled<R> {
+    pattern: String,
+    options: Options,
+    result: Result<R, CompileError>,
+}
+
+/// Why a [`Compiled`] has no regex.
+#[derive(Clone, Debug)]
+pub enum CompileError {
+    /// The pattern is not a valid regex.
+    Regex(Error),
+    /// Options were given along with a regex that was already compiled.
+    Options,
+    /// The pattern is not a valid fancy-regex.
+    #[cfg(feature = "fancy-regex")]
+    Fancy(fancy_regex::Error),
+    /// fancy-regex has no equivalent of the named option.
+    #[cfg(feature = "fancy-regex")]
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::Regex(err) => err.fmt(f),
+            CompileError::Options => {
+                f.write_str("options can't be applied to an already compiled regex")
+            }
+            #[cfg(feature = "fancy-regex")]
+            CompileError::Fancy(err) => err.fmt(f),
+            #[cfg(feature = "fancy-regex")]
+            CompileError::Unsupported(name) => {
+                write!(f, "the `{name}` option is not supported by fancy-regex")
+            }
+        }
+    }
+}
+
+impl<R: FromPattern> Compiled<R> {
+    /// Compiles `pattern`, keeping the error if it is invalid.
+    pub fn new(pattern: &str) -> Self {
+        Compiled::with_options(pattern, Options::new())
+    }
+
+    /// Compiles `pattern` with `options`, keeping the error if it is invalid.
+    pub fn with_options(pattern: &str, options: Options) -> Self {
+        Compiled {
+            pattern: pattern.to_owned(),
+            result: R::from_pattern(pattern, &options),
+            options,
+        }
+    }
+}
+
+impl<R> Compiled<R> {
+    /// Wraps a regex that was compiled elsewhere, which can't have `options`
+    /// applied to it after the fact.
+    fn from_regex(pattern: &str, re: R, options: Options) -> Self {
+        let result = if options.is_empty() {
+            Ok(re)
+        } else {
+            Err(CompileError::Options)
+        };
+        Compiled {
+            pattern: pattern.to_owned(),
+            options,
+            result,
+        }
+    }
+
+    /// The pattern as written, even if it failed to compile.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// The options that the pattern was compiled with.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// The compiled regex, or the reason that the pattern is invalid.
+    pub fn result(&self) -> Result<&R, &CompileError> {
+        self.result.as_ref()
+    }
+
+    /// The compiled regex, panicking if the pattern is invalid.
+    #[track_caller]
+    pub fn get(&self) -> &R {
+        self.result.as_ref().expect("a valid regex")
+    }
+}
+
+/// A regex compiled on first use, for patterns that are known at the call
+/// site. Each literal pattern gets its own `static` cache, so assertions in a
+/// loop only pay the compile cost once.
+pub struct RegexCache<R = Regex>(OnceLock<Compiled<R>>);
+
+impl<R: FromPattern> RegexCache<R> {
+    /// Creates an empty cache.
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        RegexCache(OnceLock::new())
+    }
+
+    /// Returns the compiled `pattern`, compiling it if this is the first call.
+    pub fn get(&self, pattern: &str) -> &Compiled<R> {
+        self.0.get_or_init(|| Compiled::new(pattern))
+    }
+}
+
+/// Several patterns compiled into one [`RegexSet`], along with the result.
+#[cfg(feature = "regex")]
+pub struct CompiledSet {
+    patterns: Vec<String>,
+    result: Result<RegexSet, (String, regex::Error)>,
+}
+
+#[cfg(feature = "regex")]
+impl CompiledSet {
+    /// Compiles `patterns`, keeping the first invalid pattern and its error if
+    /// there is one.
+    pub fn new(patterns: &[&str]) -> Self {
+        let result = RegexSet::new(patterns).map_err(|err| {
+            // The set's error does not say which pattern is at fault, so
+            // compile them one at a time to find out.
+            patterns
+                .iter()
+                .find_map(|pattern| Some((pattern.to_string(), Regex::new(pattern).err()?)))
+                .unwrap_or_else(|| (patterns.join("|"), err))
+        });
+        CompiledSet {
+            patterns: patterns.iter().map(|pattern| pattern.to_string()).collect(),
+            result,
+        }
+    }
+
+    /// The patterns as written, even if they failed to compile.
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// The compiled set, or the invalid pattern and the reason that it is
+    /// invalid.
+    pub fn result(&self) -> Result<&RegexSet, (&str, &regex::Error)> {
+        self.result
+            .as_ref()
+            .map_err(|(pattern, err)| (pattern.as_str(), err))
+    }
+}
+
+/// A [`CompiledSet`] compiled on first use, for sets of patterns that are all
+/// known at the call site.
+#[cfg(feature = "regex")]
+pub struct RegexSetCache(OnceLock<CompiledSet>);
+
+#[cfg(feature = "regex")]
+impl RegexSetCache {
+    /// Creates an empty cache.
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        RegexSetCache(OnceLock::new())
+    }
+
+    /// Returns the compiled `patterns`, compiling them if this is the first
+    /// call.
+    pub fn get(&self, patterns: &[&str]) -> &CompiledSet {
+        self.0.get_or_init(|| CompiledSet::new(patterns))
+    }
+}
+
+/// Borrows a pattern of any string type as a `&str`.
+pub fn as_pattern<P: AsRef<str> + ?Sized>(pattern: &P) -> &str {
+    pattern.as_ref()
+}
+
+/// Joins pattern fragments into a verbose-mode pattern, with `(?x)` on the
+/// first line and one fragment per line after it.
+pub fn verbose<P: AsRef<str>>(fragments: &[P]) -> String {
+    let mut pattern = "(?x)".to_owned();
+    for fragment in fragments {
+        pattern.push('\n');
+        pattern.push_str(fragment.as_ref());
+    }
+    pattern
+}
+
+/// A regex that only matches starting at the beginning of the haystack, and
+/// that prefers the longest match over the leftmost-first one.
+#[cfg(feature = "regex")]
+pub struct FullRegex {
+    pattern: String,
+    re: meta::Regex,
+}
+
+#[cfg(feature = "regex")]
+impl FromPattern for FullRegex {
+    fn from_pattern(pattern: &str, options: &Options) -> Result<Self, CompileError> {
+        let re = meta::Regex::builder()
+            .syntax(options.syntax_config())
+            .configure(options.meta_config().match_kind(MatchKind::All))
+            .build(pattern)
+            .map_err(|err| match (err.size_limit(), err.syntax_error()) {
+                (Some(limit), _) => regex::Error::CompiledTooBig(limit),
+                (None, Some(syntax)) => regex::Error::Syntax(syntax.to_string()),
+                (None, None) => regex::Error::Syntax(err.to_string()),
+            })
+            .map_err(CompileError::Regex)?;
+        Ok(FullRegex {
+            pattern: pattern.to_owned(),
+            re,
+        })
+    }
+}
+
+#[cfg(feature = "regex")]
+impl FullRegex {
+    /// The pattern that this regex was compiled from.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the longest prefix of `haystack` that the regex matches.
+    pub fn longest_prefix<'h>(&self, haystack: &'h str) -> Option<&'h str> {
+        let input = Input::new(haystack).anchored(Anchored::Yes);
+        let m = self.re.find(input)?;
+        Some(&haystack[..m.end()])
+    }
+
+    /// Explains why `haystack` is not fully matched, or returns `None` if it
+    /// is.
+    pub fn mismatch(&self, haystack: &str) -> Option<String> {
+        match self.longest_prefix(haystack) {
+            Some(prefix) if prefix.len() == haystack.len() => None,
+            Some(prefix) => Some(format!("longest matching prefix is `{prefix:?}`")),
+            None => Some("no prefix matches".to_owned()),
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Asserting how many times a regex matches.
+
+use crate::backend::Regex;
+use crate::haystack::Text;
+use crate::options::Options;
+use crate::render::{Excerpt, HaystackName, Pattern, PatternLines, WithOptions};
+use std::fmt::{self, Write as _};
+use std::ops::{Range, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive};
+
+/// An expected number of matches for [`assert_match_count!`]: either an exact
+/// count or a range of counts.
+///
+/// It is implemented for `usize` and for every kind of range of `usize`, such
+/// as `1..`, `2..=4`, or `..3`. Integer literals are inferred as `usize`, but a
+/// count of another integer type, such as a `u32`, has to be converted first,
+/// as in `n as usize`.
+///
+/// [`assert_match_count!`]: macro.assert_match_count.html
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a count of matches",
+    note = "counts are a `usize` or a range of them; convert other integers with `as usize`"
+)]
+pub trait MatchCount {
+    /// Whether `count` matches are expected.
+    fn allows(&self, count: usize) -> bool;
+
+    /// Describes the expected count, as in "expected {}".
+    fn describe(&self) -> String;
+}
+
+impl MatchCount for usize {
+    fn allows(&self, count: usize) -> bool {
+        count == *self
+    }
+
+    fn describe(&self) -> String {
+        format!("exactly {self}")
+    }
+}
+
+impl MatchCount for Range<usize> {
+    fn allows(&self, count: usize) -> bool {
+        self.contains(&count)
+    }
+
+    fn describe(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl MatchCount for RangeInclusive<usize> {
+    fn allows(&self, count: usize) -> bool {
+        self.contains(&count)
+    }
+
+    fn describe(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl MatchCount for RangeFrom<usize> {
+    fn allows(&self, count: usize) -> bool {
+        self.contains(&count)
+    }
+
+    fn describe(&self) -> String {
+        format!("at least {}", self.start)
+    }
+}
+
+impl MatchCount for RangeTo<usize> {
+    fn allows(&self, count: usize) -> bool {
+        self.contains(&count)
+    }
+
+    fn describe(&self) -> String {
+        format!("fewer than {}", self.end)
+    }
+}
+
+impl MatchCount for RangeToInclusive<usize> {
+    fn allows(&self, count: usize) -> bool {
+        self.contains(&count)
+    }
+
+    fn describe(&self) -> String {
+        format!("at most {}", self.end)
+    }
+}
+
+/// Panics unless `re` matches `haystack` as many times as `expected` allows,
+/// listing every match that was found.
+#[track_caller]
+pub fn assert_count<C: MatchCount>(
+    haystack: Text<'_>,
+    re: &Regex,
+    options: &Options,
+    expected: C,
+    args: Option<fmt::Arguments<'_>>,
+) {
+    let note = haystack.note();
+    let haystack = haystack.as_str();
+    let matches: Vec<_> = re.find_iter(haystack).collect();
+    if expected.allows(matches.len()) {
+        return;
+    }
+    let times = if matches.len() == 1 { "time" } else { "times" };
+    let summary = format!(
+        "{} matches {}{} {} {times}, expected {}",
+        HaystackName(haystack),
+        Pattern(re.as_str()),
+        WithOptions(options),
+        matches.len(),
+        expected.describe(),
+    );
+    let excerpt = Excerpt {
+        haystack,
+        range: None,
+        color: false,
+    };
+    let mut details = format!("{}{excerpt}", PatternLines(re.as_str()));
+    if !matches.is_empty() {
+        details += "\nmatches:";
+        for m in &matches {
+            write!(details, "\n    {:?}: `{:?}`", m.range(), m.as_str()).unwrap();
+        }
+    }
+    match args {
+        Some(args) => panic!("assertion failed: {summary}: {args}{details}{note}"),
+        None => panic!("assertion failed: {summary}{details}{note}"),
+    }
+}
+
+/// Asserts that a regex matches a string a given number of times, using
+/// [`regex::Regex`].
+///
+/// Matches are counted with [`Regex::find_iter`], so they do not overlap. The
+/// expected count is a [`MatchCount`]: either a `usize` or a range of them,
+/// such as `1..` for "at least one" or `..=2` for "at most two". Counts of
+/// other integer types have to be converted with `as usize`. On failure, the
+/// panic message lists every match along with its byte range.
+///
+/// Options, as described for [`assert_matches_regex!`], go after the count.
+///
+/// [`regex::Regex`]: https://docs.rs/regex/*/regex/struct.Regex.html
+/// [`Regex::find_iter`]: https://docs.rs/regex/*/regex/struct.Regex.html#method.find_iter
+/// [`assert_matches_regex!`]: macro.assert_matches_regex.html
+/// [`MatchCount`]: trait.MatchCount.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_match_count;
+/// let output = "warning: unused\nwarning: dead code\nretrying\n";
+/// assert_match_count!(output, "(?m)^warning:", 2);
+/// assert_match_count!(output, "error", 0);
+/// assert_match_count!(output, "retry", 1..);
+/// assert_match_count!(output, "error", ..=2);
+/// assert_match_count!(output, "^WARNING:", 2, case_insensitive, multi_line);
+///
+/// let expected: u32 = 2;
+/// assert_match_count!(output, "warning", expected as usize);
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_match_count;
+/// let output = "warning: unused\nwarning: dead code\n";
+/// assert_match_count!(output, "warning", 1, "expected a single warning");
+/// ```
+#[macro_export]
+macro_rules! assert_match_count {
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $count:expr $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $re);
+        let re = $crate::__unwrap_regex!("assert_match_count", compiled);
+        $crate::__private::assert_count(
+            haystack,
+            re,
+            compiled.options(),
+            $count,
+            ::std::option::Option::None,
+        );
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $count:expr, $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $re);
+        let re = $crate::__unwrap_regex!("assert_match_count", compiled, $($arg)*);
+        $crate::__private::assert_count(
+            haystack,
+            re,
+            compiled.options(),
+            $count,
+            ::std::option::Option::Some(::std::format_args!($($arg)*)),
+        );
+    }};
+    ($haystack:expr, $re:expr, $count:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_match_count [] [$haystack, $re, $count] $($($rest)*)?)
+    };
+}
@@ -0,0 +1,114 @@
+//! Asserting that a value's `Debug` form matches a regex.
+
+use crate::backend::Regex;
+use crate::check::invalid_regex;
+use crate::compile::Compiled;
+use crate::render::Mismatch;
+use std::fmt;
+
+/// Panics unless the `Debug` form of `value`, pretty-printed with `{:#?}` if
+/// `pretty` is set, matches `re`. The value is referred to by `expr`, the
+/// expression it came from.
+#[track_caller]
+pub fn assert_debug(
+    value: &dyn fmt::Debug,
+    expr: &str,
+    pretty: bool,
+    re: &Compiled<Regex>,
+    args: Option<fmt::Arguments<'_>>,
+) {
+    let (haystack, format) = if pretty {
+        (format!("{value:#?}"), "{:#?}")
+    } else {
+        (format!("{value:?}"), "{:?}")
+    };
+    let regex = match re.result() {
+        Ok(regex) => regex,
+        Err(_) => invalid_regex("assert_debug_matches_regex", re, args),
+    };
+    if regex.is_match(&haystack) {
+        return;
+    }
+    let name = format!("`{format}` of `{expr}`");
+    let mismatch = Mismatch::new(&haystack, re.pattern(), re.options()).named(&name);
+    match args {
+        Some(args) => panic!(
+            "assertion failed: {}: {args}{}",
+            mismatch.summary(),
+            mismatch.details(),
+        ),
+        None => panic!(
+            "assertion failed: {}{}",
+            mismatch.summary(),
+            mismatch.details()
+        ),
+    }
+}
+
+/// Asserts that the `Debug` form of a value matches a regex using
+/// [`regex::Regex`].
+///
+/// This saves writing `format!("{:?}", value)` as the haystack, and the panic
+/// message refers to the value by the expression that was passed in. Pass
+/// `"{:#?}"` before the value to use the pretty-printed form instead, which is
+/// shown as numbered lines on failure, or `"{:?}"` to spell out the default. Options and a message can follow the
+/// regex, as with [`assert_matches_regex!`].
+///
+/// [`regex::Regex`]: https://docs.rs/regex/*/regex/struct.Regex.html
+/// [`assert_matches_regex!`]: macro.assert_matches_regex.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_debug_matches_regex;
+/// assert_debug_matches_regex!(vec![1, 2, 3], r"^\[1, .*3\]$");
+/// assert_debug_matches_regex!(Some("hi"), r#"^Some\("hi"\)$"#);
+/// assert_debug_matches_regex!("{:#?}", (1, "a"), r#"(?m)^    "a",$"#);
+/// ```
+///
+/// On failure, the panic message looks like this:
+///
+/// ```text
+/// assertion failed: `{:?}` of `vec![1, 2, 3]` does not match `4`
+///     "[1, 2, 3]"
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_debug_matches_regex;
+/// let result: Result<u32, String> = Err("timed out".into());
+/// assert_debug_matches_regex!(result, r"^Ok\(", "request failed");
+/// ```
+#[macro_export]
+macro_rules! assert_debug_matches_regex {
+    (@options [$($opt:tt)*] $pretty:literal, $value:expr, $re:expr $(,)?) => {{
+        let re = $crate::__regex!(@options [$($opt)*] $re);
+        $crate::__private::assert_debug(
+            &$value,
+            ::std::stringify!($value),
+            $pretty,
+            re,
+            ::std::option::Option::None,
+        );
+    }};
+    (@options [$($opt:tt)*] $pretty:literal, $value:expr, $re:expr, $($arg:tt)+) => {{
+        let re = $crate::__regex!(@options [$($opt)*] $re);
+        $crate::__private::assert_debug(
+            &$value,
+            ::std::stringify!($value),
+            $pretty,
+            re,
+            ::std::option::Option::Some(::std::format_args!($($arg)*)),
+        );
+    }};
+    ("{:?}", $value:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_debug_matches_regex [] [false, $value, $re] $($($rest)*)?)
+    };
+    ("{:#?}", $value:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_debug_matches_regex [] [true, $value, $re] $($($rest)*)?)
+    };
+    ($value:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_debug_matches_regex [] [false, $value, $re] $($($rest)*)?)
+    };
+}
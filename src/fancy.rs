@@ -0,0 +1,107 @@
+//! Asserting with fancy-regex, for lookaround and backreferences.
+
+use crate::check::invalid_regex;
+use crate::compile::Compiled;
+use crate::haystack::Text;
+use crate::render::{self, Excerpt, HaystackName, Mismatch, Pattern, PatternLines, WithOptions};
+use fancy_regex::Regex;
+use std::fmt;
+
+/// Panics unless `re` matches `haystack`. If fancy-regex gives up before it
+/// can tell, such as when it runs into its backtrack limit, the panic says so
+/// instead of reporting a mismatch.
+#[track_caller]
+pub fn assert_fancy(haystack: Text<'_>, re: &Compiled<Regex>, args: Option<fmt::Arguments<'_>>) {
+    let regex = match re.result() {
+        Ok(regex) => regex,
+        Err(_) => invalid_regex("assert_matches_fancy_regex", re, args),
+    };
+    let note = haystack.note();
+    let haystack = haystack.as_str();
+    let (summary, details) = match regex.is_match(haystack) {
+        Ok(true) => return,
+        Ok(false) => {
+            let mismatch = Mismatch::without_partial(haystack, re.pattern(), re.options());
+            (
+                mismatch.summary().to_string(),
+                mismatch.details().to_string(),
+            )
+        }
+        Err(err) => {
+            let summary = format!(
+                "{} could not be matched against {}{}",
+                HaystackName(haystack),
+                Pattern(re.pattern()),
+                WithOptions(re.options()),
+            );
+            let excerpt = Excerpt {
+                haystack,
+                range: None,
+                color: render::use_color(),
+            };
+            let details = format!("{}\n{err}{excerpt}", PatternLines(re.pattern()));
+            (summary, details)
+        }
+    };
+    match args {
+        Some(args) => panic!("assertion failed: {summary}: {args}{details}{note}"),
+        None => panic!("assertion failed: {summary}{details}{note}"),
+    }
+}
+
+/// Asserts that a string matches a regex using [`fancy_regex::Regex`], which
+/// supports lookaround and backreferences. Requires the `fancy-regex`
+/// feature.
+///
+/// The panic message does not point out how much of the regex matched, as
+/// it does for the other macros, since that is worked out with the syntax of
+/// the regex engine that the crate was built with.
+///
+/// fancy-regex backtracks to match those, and gives up once it has
+/// backtracked too many times. That fails the assertion with its own message,
+/// rather than one that says the string does not match. The limit can be set
+/// with the `backtrack_limit` option. Other options, fragments, and a message
+/// can be passed as with [`assert_matches_regex!`], except for
+/// `line_terminator`, `swap_greed`, `octal`, and `nest_limit`, which
+/// fancy-regex has no equivalent for.
+///
+/// [`fancy_regex::Regex`]: https://docs.rs/fancy-regex/*/fancy_regex/struct.Regex.html
+/// [`assert_matches_regex!`]: macro.assert_matches_regex.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_matches_fancy_regex;
+/// assert_matches_fancy_regex!("foobaz", "foo(?!bar)");
+/// assert_matches_fancy_regex!("<b>bold</b>", r"^<(\w+)>.*</\1>$");
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_matches_fancy_regex;
+/// let html = "<b>bold</i>";
+/// assert_matches_fancy_regex!(html, r"^<(\w+)>.*</\1>$", "unbalanced tags in `{html}`");
+/// ```
+#[macro_export]
+macro_rules! assert_matches_fancy_regex {
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let re = $crate::__regex!(@options [$($opt)*] fancy; $re);
+        $crate::__private::assert_fancy(haystack, re, ::std::option::Option::None);
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let re = $crate::__regex!(@options [$($opt)*] fancy; $re);
+        $crate::__private::assert_fancy(
+            haystack,
+            re,
+            ::std::option::Option::Some(::std::format_args!($($arg)*)),
+        );
+    }};
+    ($haystack:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_matches_fancy_regex [] [$haystack, $re] $($($rest)*)?)
+    };
+}
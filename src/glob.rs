@@ -0,0 +1,232 @@
+//! Asserting that a string matches a glob, by way of a regex.
+
+use crate::backend::{escape, Regex};
+use crate::check::assert_translated;
+use crate::compile::Compiled;
+use crate::haystack::Text;
+use crate::options::Options;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// A glob, along with the regex that it was translated into.
+pub struct Glob {
+    glob: String,
+    re: Compiled<Regex>,
+}
+
+impl Glob {
+    /// Translates `glob` into a regex and compiles it with `options`.
+    pub fn new(glob: &str, options: Options) -> Self {
+        Glob {
+            glob: glob.to_owned(),
+            re: Compiled::with_options(&to_regex(glob), options),
+        }
+    }
+}
+
+/// A [`Glob`] translated on first use, for globs that are known at the call
+/// site.
+pub struct GlobCache(OnceLock<Glob>);
+
+impl GlobCache {
+    /// Creates an empty cache.
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        GlobCache(OnceLock::new())
+    }
+
+    /// Returns the translated `glob`, translating it if this is the first call.
+    pub fn get(&self, glob: &str) -> &Glob {
+        self.0.get_or_init(|| Glob::new(glob, Options::new()))
+    }
+}
+
+/// Translates `glob` into a regex that matches the same strings. Its text is
+/// escaped, except for `*`, `?`, and character classes in brackets. A `[`
+/// that is never closed is taken literally.
+///
+/// The regex is anchored at both ends, and `*` and `?` match newlines too. So
+/// do negated classes, as they do in a regex. Newlines in the glob are written
+/// as `\n`, to keep the regex on one line.
+pub(crate) fn to_regex(glob: &str) -> String {
+    let mut regex = String::from("(?s)^");
+    let mut rest = glob;
+    while let Some(i) = rest.find(['*', '?', '[']) {
+        regex += &literal(&rest[..i]);
+        let special = rest.as_bytes()[i];
+        rest = &rest[i + 1..];
+        match special {
+            b'*' => regex += ".*",
+            b'?' => regex.push('.'),
+            _ => match class(rest) {
+                Some((class, len)) => {
+                    regex += &class;
+                    rest = &rest[len..];
+                }
+                None => regex += r"\[",
+            },
+        }
+    }
+    regex += &literal(rest);
+    regex.push('$');
+    regex
+}
+
+/// Escapes `text`, writing newlines as `\n`.
+pub(crate) fn literal(text: &str) -> String {
+    escape(text).replace('\n', r"\n")
+}
+
+/// Translates the character class at the start of `glob`, which follows its
+/// opening `[`. Returns the class and the length of `glob` that it took up,
+/// including the closing `]`, or `None` if there is no closing `]`.
+///
+/// A class is negated by `!` or `^` right after the `[`. A `]` right after
+/// that is part of the class rather than the end of it, as is a `-` at either
+/// end.
+fn class(glob: &str) -> Option<(String, usize)> {
+    let (negated, start) = match glob.chars().next() {
+        Some('!' | '^') => (true, 1),
+        _ => (false, 0),
+    };
+    let (len, _) = glob[start..]
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| c == ']')?;
+    let end = start + len;
+    let chars: Vec<char> = glob[start..end].chars().collect();
+    let mut class = String::from(if negated { "[^" } else { "[" });
+    let mut i = 0;
+    while i < chars.len() {
+        if i + 2 < chars.len() && chars[i + 1] == '-' {
+            class += &format!("{}-{}", literal_char(chars[i]), literal_char(chars[i + 2]));
+            i += 3;
+        } else {
+            class += &literal_char(chars[i]);
+            i += 1;
+        }
+    }
+    class.push(']');
+    Some((class, end + 1))
+}
+
+fn literal_char(c: char) -> String {
+    literal(c.encode_utf8(&mut [0; 4]))
+}
+
+/// Panics unless `glob` matches the whole of `haystack`, showing the regex
+/// that the glob was translated into.
+#[track_caller]
+pub fn assert_glob(haystack: Text<'_>, glob: &Glob, args: Option<fmt::Arguments<'_>>) {
+    assert_translated(
+        "assert_matches_glob",
+        "glob",
+        &glob.glob,
+        &glob.re,
+        haystack,
+        args,
+    );
+}
+
+/// Asserts that a string matches a glob, such as `"error: * not found"`.
+///
+/// A glob is written as the string it matches, without escaping, except for
+/// these:
+///
+/// - `*` matches any run of characters, including none.
+/// - `?` matches any one character.
+/// - `[...]` matches one of the characters in brackets, such as `[abc]` or
+///   `[0-9]`. It matches one that is not in brackets if it starts with `!` or
+///   `^`, as in `[!0-9]`. To match `*`, `?`, or `[` literally, put it in
+///   brackets: `[*]`.
+///
+/// Unlike a regex, a glob must match the whole string, and `*` and `?` match
+/// newlines too. The glob is translated into a regex using [`escape`], which
+/// the panic message shows along with the glob. Options, such as
+/// `case_insensitive`, apply to that regex, and they and a message can be
+/// passed as with [`assert_matches_regex!`].
+///
+/// [`escape`]: fn.escape.html
+/// [`assert_matches_regex!`]: macro.assert_matches_regex.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_matches_glob;
+/// assert_matches_glob!("error: file.txt not found", "error: * not found");
+/// assert_matches_glob!("v1.2.3 (2024-01-01)", "v?.?.? (*)");
+/// assert_matches_glob!("exit code 3", "exit code [0-9]");
+/// assert_matches_glob!("ERROR: disk full", "error: *", case_insensitive);
+/// ```
+///
+/// On failure, the panic message looks like this:
+///
+/// ```text
+/// assertion failed: `"error: file.txt is missing"` does not match glob `error: * not found`
+/// regex: `(?s)^error: .* not found$`
+/// the longest matching prefix of the regex is `(?s)^error: .* `, which stops here:
+///     "error: file.txt is missing"
+///                         ^
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_matches_glob;
+/// let path = "target/debug/app.exe";
+/// assert_matches_glob!(path, "target/*/app", "unexpected binary at `{path}`");
+/// ```
+#[macro_export]
+macro_rules! assert_matches_glob {
+    (@options [$($opt:tt)*] $haystack:expr, $glob:expr $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let glob = $crate::__glob!(@options [$($opt)*] $glob);
+        $crate::__private::assert_glob(haystack, glob, ::std::option::Option::None);
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $glob:expr, $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let glob = $crate::__glob!(@options [$($opt)*] $glob);
+        $crate::__private::assert_glob(
+            haystack,
+            glob,
+            ::std::option::Option::Some(::std::format_args!($($arg)*)),
+        );
+    }};
+    ($haystack:expr, $glob:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_matches_glob [] [$haystack, $glob] $($($rest)*)?)
+    };
+}
+
+/// Evaluates to a `&Glob`, translated once per call site if it is a literal
+/// without options.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __glob {
+    (@options [] $glob:literal) => {{
+        static CACHE: $crate::__private::GlobCache = $crate::__private::GlobCache::new();
+        CACHE.get($glob)
+    }};
+    (@options [$($opt:tt)*] $glob:expr) => {
+        &$crate::__private::Glob::new(&$glob, $crate::__private::Options::new() $($opt)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_regex;
+
+    #[test]
+    fn translate() {
+        assert_eq!(to_regex("error: * not found"), "(?s)^error: .* not found$");
+        assert_eq!(to_regex("v?.?"), r"(?s)^v.\..$");
+        assert_eq!(to_regex("a\nb (c)"), r"(?s)^a\nb \(c\)$");
+        assert_eq!(to_regex("[a-z]x[!0-9_]"), r"(?s)^[a-z]x[^0-9_]$");
+        assert_eq!(to_regex("[*][?][[]"), r"(?s)^[\*][\?][\[]$");
+        assert_eq!(to_regex("[]a-][!]]"), r"(?s)^[\]a\-][^\]]$");
+        assert_eq!(to_regex("[a-]"), r"(?s)^[a\-]$");
+        assert_eq!(to_regex("a[b"), r"(?s)^a\[b$");
+        assert_eq!(to_regex("[]"), r"(?s)^\[\]$");
+    }
+}
@@ -0,0 +1,193 @@
+//! Converting haystacks of various types to strings.
+
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::fmt;
+use std::ops::Deref;
+use std::path::Path;
+
+/// A value that the assertion macros can match a regex against.
+///
+/// The macros call [`to_haystack`] through auto-deref, so implementing this
+/// for `str`, `Path`, and `OsStr` also covers `String`, `PathBuf`,
+/// `OsString`, and references to any of them. It is also implemented for
+/// `fmt::Arguments`, as made by [`format_args!`].
+///
+/// Paths and OS strings are converted lossily, replacing invalid UTF-8 with
+/// U+FFFD, and the panic message notes when that happened.
+///
+/// Implement it for your own types to match against them directly, such as
+/// with their `Display` form:
+///
+/// ```
+/// use assert_matches_regex::{assert_matches_regex, Haystack};
+/// use std::borrow::Cow;
+/// use std::fmt;
+///
+/// struct Version(u32, u32);
+///
+/// impl fmt::Display for Version {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "v{}.{}", self.0, self.1)
+///     }
+/// }
+///
+/// impl Haystack for Version {
+///     fn to_haystack(&self) -> Cow<'_, str> {
+///         Cow::Owned(self.to_string())
+///     }
+/// }
+///
+/// assert_matches_regex!(Version(1, 80), r"^v1\.\d+$");
+/// ```
+///
+/// [`to_haystack`]: #tymethod.to_haystack
+/// [`format_args!`]: https://doc.rust-lang.org/std/macro.format_args.html
+pub trait Haystack {
+    /// The string to match against.
+    fn to_haystack(&self) -> Cow<'_, str>;
+
+    /// Whether [`to_haystack`] had to replace invalid UTF-8, which the panic
+    /// message will point out.
+    ///
+    /// [`to_haystack`]: #tymethod.to_haystack
+    fn is_lossy(&self) -> bool {
+        false
+    }
+}
+
+impl Haystack for str {
+    fn to_haystack(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl Haystack for Path {
+    fn to_haystack(&self) -> Cow<'_, str> {
+        self.to_string_lossy()
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.to_str().is_none()
+    }
+}
+
+impl Haystack for OsStr {
+    fn to_haystack(&self) -> Cow<'_, str> {
+        self.to_string_lossy()
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.to_str().is_none()
+    }
+}
+
+impl Haystack for fmt::Arguments<'_> {
+    fn to_haystack(&self) -> Cow<'_, str> {
+        match self.as_str() {
+            Some(s) => Cow::Borrowed(s),
+            None => Cow::Owned(self.to_string()),
+        }
+    }
+}
+
+/// A haystack converted to a string, remembering whether the conversion was
+/// lossy so that panic messages can say so.
+#[derive(Clone, Copy)]
+pub struct Text<'a> {
+    text: &'a str,
+    lossy: bool,
+}
+
+impl<'a> Text<'a> {
+    /// Wraps the result of [`Haystack::to_haystack`].
+    pub fn new(text: &'a str, lossy: bool) -> Self {
+        Text { text, lossy }
+    }
+
+    /// The haystack as a string.
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// Whether the haystack was converted lossily.
+    pub fn is_lossy(&self) -> bool {
+        self.lossy
+    }
+
+    /// A note to end the panic message with, if the haystack was converted
+    /// lossily.
+    pub fn note(&self) -> LossyNote {
+        LossyNote(self.lossy)
+    }
+}
+
+impl<'a> From<&'a str> for Text<'a> {
+    fn from(text: &'a str) -> Self {
+        Text::new(text, false)
+    }
+}
+
+impl Deref for Text<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.text
+    }
+}
+
+/// See [`Text::note`].
+pub struct LossyNote(pub(crate) bool);
+
+impl fmt::Display for LossyNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 {
+            f.write_str("\nnote: the haystack is not valid UTF-8, so it was converted lossily, replacing invalid sequences with U+FFFD")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Shadows the variable `$haystack` with a `Text` of it, converted with
+/// [`Haystack`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __haystack {
+    ($haystack:ident) => {
+        let (text, lossy) = {
+            // A method call, so that `$haystack` is auto-dereferenced down to
+            // whichever type implements the trait.
+            use $crate::Haystack as _;
+            ($haystack.to_haystack(), $haystack.is_lossy())
+        };
+        let $haystack = $crate::__private::Text::new(&text, lossy);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Haystack;
+    use std::ffi::OsStr;
+    use std::path::Path;
+
+    #[test]
+    fn lossless() {
+        assert_eq!("abc".to_haystack(), "abc");
+        assert!(!"abc".is_lossy());
+        assert_eq!(Path::new("a/b").to_haystack(), "a/b");
+        assert!(!Path::new("a/b").is_lossy());
+        assert_eq!(format_args!("{}-{}", 1, 2).to_haystack(), "1-2");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lossy() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let os = OsStr::from_bytes(b"a\xFFb");
+        assert_eq!(os.to_haystack(), "a\u{FFFD}b");
+        assert!(os.is_lossy());
+        assert!(Path::new(os).is_lossy());
+    }
+}
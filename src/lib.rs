@@ -1,21 +1,272 @@
 //! Provides a macro, [`assert_matches_regex!`], which tests whether a string
 //! matches a given regex, causing a panic if it does not match.
 //!
+//! Its negation, [`assert_not_matches_regex!`], panics if the string does
+//! match, and [`assert_captures!`] evaluates to the capture groups of the
+//! match so that they can be checked further. [`assert_full_match_regex!`]
+//! requires the regex to match the entire string. [`assert_matches_bytes_regex!`]
+//! works on byte strings that need not be valid UTF-8, and
+//! [`assert_debug_matches_regex!`] on the `Debug` form of any value.
+//!
+//! For output made of many lines, [`assert_all_lines_match!`],
+//! [`assert_any_line_matches!`], and [`assert_no_line_matches!`] run the
+//! regex against each line and report the offending lines by number.
+//!
+//! [`assert_matches_in_order!`] checks that several regexes match one after
+//! another, such as events in a log, and [`assert_match_count!`] checks how
+//! many times a regex matches. [`assert_matches_all_regex!`] and
+//! [`assert_matches_any_regex!`] check a string against several regexes in
+//! one pass and report every one that did not match.
+//!
+//! [`debug_assert_matches_regex!`] is like [`assert_matches_regex!`], but
+//! only checked in debug builds.
+//!
+//! For expectations that are mostly literal text, [`assert_matches_glob!`]
+//! takes a glob such as `"error: * not found"` instead of a regex, and
+//! [`assert_matches_template!`] a template such as
+//! `"Finished in {duration} with {int} tests"`, whose placeholders stand for
+//! common kinds of values.
+//!
+//! To get the failure as a value instead of a panic, use
+//! [`check_matches_regex`].
+//!
+//! # Features
+//!
+//! The regexes are compiled with the [`regex`] crate by default. To build
+//! faster, turn off default features and turn on `regex-lite` to use the
+//! [`regex-lite`] crate instead. The two features are mutually exclusive. Panic
+//! messages are formatted the same with either, but `regex-lite` has no byte
+//! regexes or regex sets, so [`assert_matches_bytes_regex!`],
+//! [`assert_full_match_regex!`], [`assert_matches_all_regex!`], and
+//! [`assert_matches_any_regex!`] are only available with `regex`, as are the
+//! `line_terminator`, `unicode`, `octal`, and `dfa_size_limit` options.
+//!
+//! ```toml
+//! [dev-dependencies]
+//! assert_matches_regex = { version = "0.1", default-features = false, features = ["regex-lite"] }
+//! ```
+//!
+//! With either engine, the `fancy-regex` feature adds
+//! [`assert_matches_fancy_regex!`], which uses the [`fancy-regex`] crate for
+//! patterns with lookaround or backreferences.
+//!
+//! [`regex`]: https://docs.rs/regex
+//! [`regex-lite`]: https://docs.rs/regex-lite
+//! [`fancy-regex`]: https://docs.rs/fancy-regex
+//! [`assert_matches_fancy_regex!`]: macro.assert_matches_fancy_regex.html
+//!
 //! [`assert_matches_regex!`]: macro.assert_matches_regex.html
+//! [`assert_not_matches_regex!`]: macro.assert_not_matches_regex.html
+//! [`assert_captures!`]: macro.assert_captures.html
+//! [`assert_full_match_regex!`]: macro.assert_full_match_regex.html
+//! [`assert_matches_bytes_regex!`]: macro.assert_matches_bytes_regex.html
+//! [`assert_debug_matches_regex!`]: macro.assert_debug_matches_regex.html
+//! [`assert_all_lines_match!`]: macro.assert_all_lines_match.html
+//! [`assert_any_line_matches!`]: macro.assert_any_line_matches.html
+//! [`assert_no_line_matches!`]: macro.assert_no_line_matches.html
+//! [`assert_matches_in_order!`]: macro.assert_matches_in_order.html
+//! [`assert_match_count!`]: macro.assert_match_count.html
+//! [`assert_matches_all_regex!`]: macro.assert_matches_all_regex.html
+//! [`assert_matches_any_regex!`]: macro.assert_matches_any_regex.html
+//! [`debug_assert_matches_regex!`]: macro.debug_assert_matches_regex.html
+//! [`assert_matches_glob!`]: macro.assert_matches_glob.html
+//! [`assert_matches_template!`]: macro.assert_matches_template.html
 
 #![warn(missing_docs)]
 #![warn(rust_2018_idioms)]
 
-/// A re-export of [`regex::escape`] for convenience.
+mod backend;
+mod check;
+mod compile;
+mod count;
+mod debug;
+#[cfg(feature = "fancy-regex")]
+mod fancy;
+mod glob;
+mod haystack;
+mod lines;
+mod options;
+mod partial;
+mod render;
+mod sequence;
+#[cfg(feature = "regex")]
+mod set;
+mod template;
+
+pub use crate::check::{check_matches_regex, MatchError, MatchErrorKind};
+pub use crate::compile::IntoAssertRegex;
+pub use crate::count::MatchCount;
+pub use crate::haystack::Haystack;
+pub use crate::template::register_placeholder;
+
+/// A re-export of [`regex::escape`], or of `regex_lite::escape` with the
+/// `regex-lite` feature, for convenience.
 ///
 /// [`regex::escape`]: https://docs.rs/regex/*/regex/fn.escape.html
-pub use regex::escape;
+pub use crate::backend::escape;
 
 #[doc(hidden)]
 pub mod __private {
+    pub use crate::backend::Regex;
+    pub use crate::check::{assert_capture, check, check_captures, fail, invalid_regex};
+    pub use crate::compile::{as_pattern, verbose, Compiled, FromPattern, RegexCache};
+    #[cfg(feature = "regex")]
+    pub use crate::compile::{CompiledSet, FullRegex, RegexSetCache};
+    pub use crate::count::assert_count;
+    pub use crate::debug::assert_debug;
+    #[cfg(feature = "fancy-regex")]
+    pub use crate::fancy::assert_fancy;
+    pub use crate::glob::{assert_glob, Glob, GlobCache};
+    pub use crate::haystack::Text;
+    pub use crate::lines::{assert_lines, Lines};
+    pub use crate::options::Options;
+    pub use crate::render::{BytesDisplay, Pattern, PatternLines, WithOptions};
+    pub use crate::sequence::assert_in_order;
+    #[cfg(feature = "regex")]
+    pub use crate::set::{assert_set, Patterns};
+    pub use crate::template::{assert_template, Template};
+    pub use assert_matches_regex_macros::{regex_fragments, validate_regex};
+    #[cfg(feature = "fancy-regex")]
+    pub use fancy_regex;
+    #[cfg(feature = "regex")]
     pub use regex;
 }
 
+/// Evaluates to a `&Compiled<Regex>` for the pattern, or to a `&Compiled` of
+/// another `FromPattern` type if one is given first, either as a type or as
+/// one of the keywords of `__regex_type!`. String literals, and arrays of
+/// string literal fragments, are validated at compile time and cached per
+/// call site. Already compiled regexes are used as they are, and any other
+/// pattern is compiled on every evaluation.
+///
+/// With `@options [...]` first, the bracketed `Options` builder calls are
+/// applied. Patterns with options are always compiled on every evaluation,
+/// since the options need not be constants.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __regex {
+    (@compile @options [$($opt:tt)*] $kind:ident; $re:expr) => {
+        $crate::__regex!(@compile @options [$($opt)*] $crate::__regex_type!($kind); $re)
+    };
+    (@compile @options [$($opt:tt)*] $ty:ty; $re:expr) => {
+        &{
+            // A method call, so that `$re` is auto-dereferenced down to
+            // whichever type implements the trait.
+            use $crate::IntoAssertRegex as _;
+            let compiled: $crate::__private::Compiled<$ty> =
+                (&$re).to_compiled($crate::__private::Options::new() $($opt)*);
+            compiled
+        }
+    };
+    (@compile $kind:ident; $re:expr) => {
+        $crate::__regex!(@compile $crate::__regex_type!($kind); $re)
+    };
+    (@compile $ty:ty; $re:expr) => {
+        $crate::__regex!(@compile @options [] $ty; $re)
+    };
+    (@options [] $kind:ident; $re:expr) => {
+        $crate::__regex!($kind; $re)
+    };
+    (@options [] $ty:ty; $re:expr) => {
+        $crate::__regex!($ty; $re)
+    };
+    (@options [$($opt:tt)+] $kind:ident; $re:literal) => {
+        $crate::__regex!(@compile @options [$($opt)+] $kind; $re)
+    };
+    (@options [$($opt:tt)+] $kind:ident; $re:expr) => {
+        $crate::__private::regex_fragments!($crate; @options [$($opt)+] $kind; $re)
+    };
+    (@options [$($opt:tt)+] $ty:ty; $re:literal) => {
+        $crate::__regex!(@compile @options [$($opt)+] $ty; $re)
+    };
+    (@options [$($opt:tt)+] $ty:ty; $re:expr) => {
+        $crate::__private::regex_fragments!($crate; @options [$($opt)+] $ty; $re)
+    };
+    (@options [$($opt:tt)*] $re:expr) => {
+        $crate::__regex!(@options [$($opt)*] $crate::__private::Regex; $re)
+    };
+    ($kind:ident; $re:literal) => {{
+        $crate::__private::validate_regex!($kind $re);
+        static CACHE: $crate::__private::RegexCache<$crate::__regex_type!($kind)> =
+            $crate::__private::RegexCache::new();
+        CACHE.get($re)
+    }};
+    ($kind:ident; $re:expr) => {
+        $crate::__private::regex_fragments!($crate; $kind; $re)
+    };
+    ($ty:ty; $re:literal) => {{
+        $crate::__private::validate_regex!($re);
+        static CACHE: $crate::__private::RegexCache<$ty> = $crate::__private::RegexCache::new();
+        CACHE.get($re)
+    }};
+    ($ty:ty; $re:expr) => {
+        $crate::__private::regex_fragments!($crate; $ty; $re)
+    };
+    ($re:expr) => {
+        $crate::__regex!($crate::__private::Regex; $re)
+    };
+}
+
+/// The regex type for a keyword in `__regex!`, whose literal patterns are
+/// validated differently than `Regex` ones: `bytes` for `regex::bytes::Regex`
+/// and `fancy` for `fancy_regex::Regex`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __regex_type {
+    (bytes) => {
+        $crate::__private::regex::bytes::Regex
+    };
+    (fancy) => {
+        $crate::__private::fancy_regex::Regex
+    };
+}
+
+/// Splits the options that follow the pattern in an assertion macro, such as
+/// `case_insensitive` or `size_limit(1 << 20)`, from whatever comes after
+/// them. Then calls `$mac!(@options [...] args, rest)`, where `[...]` holds
+/// the options as `Options` builder calls.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __options {
+    ($mac:ident [$($opt:tt)*] [$($args:tt)*]) => {
+        $crate::$mac!(@options [$($opt)*] $($args)*)
+    };
+    ($mac:ident [$($opt:tt)*] [$($args:tt)*] $name:ident ($value:expr) $(, $($rest:tt)*)?) => {
+        $crate::__options!($mac [$($opt)* .$name($value)] [$($args)*] $($($rest)*)?)
+    };
+    ($mac:ident [$($opt:tt)*] [$($args:tt)*] $name:ident $(, $($rest:tt)*)?) => {
+        $crate::__options!($mac [$($opt)* .$name(true)] [$($args)*] $($($rest)*)?)
+    };
+    ($mac:ident [$($opt:tt)*] [$($args:tt)*] $($rest:tt)+) => {
+        $crate::$mac!(@options [$($opt)*] $($args)*, $($rest)+)
+    };
+}
+
+/// Evaluates to the regex in a `&Compiled`, or panics the way the `$name!`
+/// macro does for an invalid pattern.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __unwrap_regex {
+    ($name:literal, $re:expr) => {
+        match $re.result() {
+            ::std::result::Result::Ok(re) => re,
+            ::std::result::Result::Err(_) => {
+                $crate::__private::invalid_regex($name, $re, ::std::option::Option::None)
+            }
+        }
+    };
+    ($name:literal, $re:expr, $($arg:tt)+) => {
+        match $re.result() {
+            ::std::result::Result::Ok(re) => re,
+            ::std::result::Result::Err(_) => $crate::__private::invalid_regex(
+                $name,
+                $re,
+                ::std::option::Option::Some(::std::format_args!($($arg)+)),
+            ),
+        }
+    };
+}
+
 /// Asserts that a string matches a regex using [`regex::Regex`].
 ///
 /// [`regex::Regex`]: https://docs.rs/regex/*/regex/struct.Regex.html
@@ -27,7 +278,24 @@ pub mod __private {
 /// assert_matches_regex!("Hello!", r"(?i)hello");
 /// ```
 ///
-/// The haystack can be a `String` or `&str`.
+/// If part of the regex matched, the panic message points out how far it got:
+///
+/// ```text
+/// assertion failed: `"version: 1.2.x"` does not match `version: \d+\.\d+\.\d+`
+/// the longest matching prefix of the regex is `version: \d+\.\d+\.`, which stops here:
+///     "version: 1.2.x"
+///                   ^
+/// ```
+///
+/// A haystack with more than one line is printed as numbered lines instead.
+/// When `CLICOLOR_FORCE` is set and `NO_COLOR` is not, the part of the
+/// haystack that the regex prefix matched is highlighted in color. Color is
+/// off otherwise, so that the panic message does not depend on where the test
+/// runs.
+///
+/// The haystack can be a `String` or `&str`, or anything else that
+/// implements [`Haystack`], such as a `Path`, an `OsStr`, or the output of
+/// `format_args!`.
 ///
 /// ```
 /// # use assert_matches_regex::assert_matches_regex;
@@ -35,6 +303,95 @@ pub mod __private {
 ///
 /// let duration = std::time::Duration::from_secs(5);
 /// assert_matches_regex!(duration.as_millis().to_string(), "^50{3}$");
+///
+/// let path = std::env::temp_dir().join("out.log");
+/// assert_matches_regex!(path, r"out\.log$");
+/// assert_matches_regex!(format_args!("{duration:?}"), "^5s$");
+/// ```
+///
+/// [`Haystack`]: trait.Haystack.html
+///
+/// When the regex is a string literal, it is checked at compile time, and an
+/// invalid pattern is reported as a compile error at the literal.
+///
+/// ```compile_fail
+/// # use assert_matches_regex::assert_matches_regex;
+/// assert_matches_regex!("abc", r"[a-z");
+/// ```
+///
+/// A pattern that is only known at runtime can't be checked ahead of time.
+/// If it turns out to be invalid, the assertion panics with a message that
+/// starts with "invalid regex in assert_matches_regex!" and includes the call
+/// site, the pattern, and where in the pattern the error is.
+///
+/// A literal pattern is also compiled only once per call site, no matter how
+/// many times the assertion runs. Other patterns are compiled on every run.
+///
+/// The regex can also be one that is already compiled, such as a shared
+/// `static` one, which is then used without being compiled again. See
+/// [`IntoAssertRegex`] for the accepted types.
+///
+/// ```
+/// # use assert_matches_regex::assert_matches_regex;
+/// # #[cfg(feature = "regex")] {
+/// use regex::Regex;
+/// use std::sync::LazyLock;
+///
+/// static VERSION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\d+\.\d+$").unwrap());
+///
+/// assert_matches_regex!("1.80", VERSION);
+/// assert_matches_regex!("1.80", &*VERSION, "not a version");
+/// # }
+/// ```
+///
+/// [`IntoAssertRegex`]: trait.IntoAssertRegex.html
+///
+/// A long pattern can be written as an array of fragments instead. They are
+/// joined one per line in verbose mode, as if the pattern started with
+/// `(?x)`, so whitespace is ignored and `#` starts a comment that runs to the
+/// end of the fragment. The panic message prints such a pattern line by line,
+/// as it was written. This works with every macro in this crate that takes a
+/// single regex.
+///
+/// ```
+/// # use assert_matches_regex::assert_matches_regex;
+/// let line = "[WARN] 2024-11-17 disk almost full";
+/// assert_matches_regex!(line, [
+///     r"^\[ (WARN|ERROR) \]           # level",
+///     r"\  \d{4}-\d{2}-\d{2}          # date",
+///     r"\  .+                         # message",
+/// ]);
+/// ```
+///
+/// Options that would otherwise be set with [`regex::RegexBuilder`] can be
+/// passed after the regex, each named after the builder method it calls.
+/// Flags such as `case_insensitive`, `multi_line`, `dot_matches_new_line`,
+/// `crlf`, `swap_greed`, `ignore_whitespace`, and `octal` are turned on by
+/// their name alone, or set with an argument, as in `unicode(false)`.
+/// `line_terminator`, `size_limit`, `dfa_size_limit`, and `nest_limit` take
+/// an argument. The options are echoed in the panic message. This works with
+/// every macro in this crate that takes a single regex, and a regex with
+/// options is compiled on every run. In [`assert_match_count!`], the options
+/// go after the count.
+///
+/// [`regex::RegexBuilder`]: https://docs.rs/regex/*/regex/struct.RegexBuilder.html
+/// [`assert_match_count!`]: macro.assert_match_count.html
+///
+/// ```
+/// # use assert_matches_regex::assert_matches_regex;
+/// let output = "Status: OK\r\nServer: test\r\n";
+/// assert_matches_regex!(output, "^status: ok$", case_insensitive, multi_line, crlf);
+/// assert_matches_regex!(output, r"\w+", size_limit(1 << 20), "output was `{output}`");
+/// ```
+///
+/// Instead of a message, the expected values of named capture groups can be
+/// passed as `name = value` pairs. The assertion then also fails if any of
+/// those groups captured something else, or did not participate in the match.
+///
+/// ```
+/// # use assert_matches_regex::assert_matches_regex;
+/// let line = "id=42 user=bob";
+/// assert_matches_regex!(line, r"id=(?<id>\d+) user=(?<u>\w+)", id = "42", u = "bob");
 /// ```
 ///
 /// An optional message in the form of a format string can be passed last.
@@ -46,32 +403,342 @@ pub mod __private {
 /// ```
 #[macro_export]
 macro_rules! assert_matches_regex {
-    ($haystack:expr, $re:expr $(,)?) => {{
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let re = $crate::__regex!(@options [$($opt)*] $re);
+        if let ::std::result::Result::Err(err) = $crate::__private::check(haystack, re) {
+            $crate::__private::fail("assert_matches_regex", &err, ::std::option::Option::None);
+        }
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $($name:ident = $expected:expr),+ $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let re = $crate::__regex!(@options [$($opt)*] $re);
+        match $crate::__private::check_captures(haystack, re) {
+            ::std::result::Result::Ok(caps) => {
+                $(
+                    $crate::__private::assert_capture(
+                        haystack,
+                        re.get(),
+                        &caps,
+                        ::std::stringify!($name),
+                        &$expected,
+                    );
+                )+
+            }
+            ::std::result::Result::Err(err) => {
+                $crate::__private::fail("assert_matches_regex", &err, ::std::option::Option::None);
+            }
+        }
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let re = $crate::__regex!(@options [$($opt)*] $re);
+        if let ::std::result::Result::Err(err) = $crate::__private::check(haystack, re) {
+            $crate::__private::fail(
+                "assert_matches_regex",
+                &err,
+                ::std::option::Option::Some(::std::format_args!($($arg)*)),
+            );
+        }
+    }};
+    ($haystack:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_matches_regex [] [$haystack, $re] $($($rest)*)?)
+    };
+}
+
+/// Asserts that a string matches a regex, in debug builds only.
+///
+/// This is [`assert_matches_regex!`] for invariants in non-test code, like
+/// [`debug_assert!`] is to [`assert!`]. It takes the same arguments, but they
+/// are only evaluated, and the regex is only compiled, when debug assertions
+/// are enabled. In builds without them, such as release builds by default, it
+/// does nothing at run time. The regex is still checked at compile time if it
+/// is a string literal.
+///
+/// [`assert_matches_regex!`]: macro.assert_matches_regex.html
+/// [`debug_assert!`]: https://doc.rust-lang.org/std/macro.debug_assert.html
+/// [`assert!`]: https://doc.rust-lang.org/std/macro.assert.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::debug_assert_matches_regex;
+/// fn identifier(name: &str) -> String {
+///     let ident = name.to_lowercase().replace('-', "_");
+///     debug_assert_matches_regex!(&ident, "^[a-z_][a-z0-9_]*$");
+///     ident
+/// }
+///
+/// assert_eq!(identifier("Max-Width"), "max_width");
+/// ```
+#[macro_export]
+macro_rules! debug_assert_matches_regex {
+    ($($arg:tt)*) => {
+        if ::std::cfg!(debug_assertions) {
+            $crate::assert_matches_regex!($($arg)*);
+        }
+    };
+}
+
+/// Asserts that a string does not match a regex using [`regex::Regex`].
+///
+/// On failure, the panic message includes the byte range of the leftmost
+/// match and the text it matched.
+///
+/// [`regex::Regex`]: https://docs.rs/regex/*/regex/struct.Regex.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_not_matches_regex;
+/// assert_not_matches_regex!("Hello!", r"(?i)goodbye");
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_not_matches_regex;
+/// let output = "token=hunter2";
+/// assert_not_matches_regex!(output, "token=", "leaked a token: `{output}`");
+/// ```
+#[macro_export]
+macro_rules! assert_not_matches_regex {
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $re);
+        let re = $crate::__unwrap_regex!("assert_not_matches_regex", compiled);
+        if let ::std::option::Option::Some(m) = re.find(&haystack) {
+            ::std::panic!(
+                "assertion failed: `{:?}` matches {}{} at {:?}: `{:?}`{}{}",
+                haystack.as_str(),
+                $crate::__private::Pattern(re.as_str()),
+                $crate::__private::WithOptions(compiled.options()),
+                m.range(),
+                m.as_str(),
+                $crate::__private::PatternLines(re.as_str()),
+                haystack.note(),
+            );
+        }
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $re);
+        let re = $crate::__unwrap_regex!("assert_not_matches_regex", compiled, $($arg)*);
+        if let ::std::option::Option::Some(m) = re.find(&haystack) {
+            ::std::panic!(
+                "assertion failed: `{:?}` matches {}{} at {:?}: `{:?}`: {}{}{}",
+                haystack.as_str(),
+                $crate::__private::Pattern(re.as_str()),
+                $crate::__private::WithOptions(compiled.options()),
+                m.range(),
+                m.as_str(),
+                ::std::format_args!($($arg)*),
+                $crate::__private::PatternLines(re.as_str()),
+                haystack.note(),
+            );
+        }
+    }};
+    ($haystack:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_not_matches_regex [] [$haystack, $re] $($($rest)*)?)
+    };
+}
+
+/// Asserts that a string matches a regex using [`regex::Regex`], and
+/// evaluates to the [`regex::Captures`] of the leftmost match.
+///
+/// The captures borrow from the haystack, so the haystack must outlive them.
+/// Pass a variable (or a reference to one) rather than a temporary.
+///
+/// [`regex::Regex`]: https://docs.rs/regex/*/regex/struct.Regex.html
+/// [`regex::Captures`]: https://docs.rs/regex/*/regex/struct.Captures.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_captures;
+/// let out = String::from("tool v2.13.0");
+/// let caps = assert_captures!(out, r"v(?<major>\d+)\.(?<minor>\d+)");
+/// assert_eq!(&caps["major"], "2");
+/// assert_eq!(&caps[2], "13");
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_captures;
+/// let out = "tool (unknown version)";
+/// assert_captures!(out, r"v(\d+)", "no version in `{out}`");
+/// ```
+#[macro_export]
+macro_rules! assert_captures {
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr $(,)?) => {{
+        let haystack: &str = &$haystack;
+        let re = $crate::__regex!(@options [$($opt)*] $re);
+        match $crate::__private::check_captures(haystack.into(), re) {
+            ::std::result::Result::Ok(caps) => caps,
+            ::std::result::Result::Err(err) => {
+                $crate::__private::fail("assert_captures", &err, ::std::option::Option::None)
+            }
+        }
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $($arg:tt)+) => {{
+        let haystack: &str = &$haystack;
+        let re = $crate::__regex!(@options [$($opt)*] $re);
+        match $crate::__private::check_captures(haystack.into(), re) {
+            ::std::result::Result::Ok(caps) => caps,
+            ::std::result::Result::Err(err) => $crate::__private::fail(
+                "assert_captures",
+                &err,
+                ::std::option::Option::Some(::std::format_args!($($arg)*)),
+            ),
+        }
+    }};
+    ($haystack:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_captures [] [$haystack, $re] $($($rest)*)?)
+    };
+}
+
+/// Asserts that a regex matches an entire string, not just part of it.
+///
+/// This is like wrapping the regex in `^(?:...)$`, except that the regex is
+/// run as an anchored search, so there's no way to get the anchoring wrong.
+/// On failure, the panic message includes the longest prefix of the string
+/// that the regex did match.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_full_match_regex;
+/// assert_full_match_regex!("deadc0de", "[a-f0-9]+");
+/// assert_full_match_regex!("samwise", "sam|samwise");
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_full_match_regex;
+/// let data = "deadc0de!";
+/// assert_full_match_regex!(data, "[a-f0-9]+", "expected `{data}` to be a hex string");
+/// ```
+#[cfg(feature = "regex")]
+#[macro_export]
+macro_rules! assert_full_match_regex {
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $crate::__private::FullRegex; $re);
+        let re = $crate::__unwrap_regex!("assert_full_match_regex", compiled);
+        if let ::std::option::Option::Some(mismatch) = re.mismatch(&haystack) {
+            ::std::panic!(
+                "assertion failed: `{:?}` does not fully match {}{} ({mismatch}){}{}",
+                haystack.as_str(),
+                $crate::__private::Pattern(re.as_str()),
+                $crate::__private::WithOptions(compiled.options()),
+                $crate::__private::PatternLines(re.as_str()),
+                haystack.note(),
+            );
+        }
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $crate::__private::FullRegex; $re);
+        let re = $crate::__unwrap_regex!("assert_full_match_regex", compiled, $($arg)*);
+        if let ::std::option::Option::Some(mismatch) = re.mismatch(&haystack) {
+            ::std::panic!(
+                "assertion failed: `{:?}` does not fully match {}{} ({mismatch}): {}{}{}",
+                haystack.as_str(),
+                $crate::__private::Pattern(re.as_str()),
+                $crate::__private::WithOptions(compiled.options()),
+                ::std::format_args!($($arg)*),
+                $crate::__private::PatternLines(re.as_str()),
+                haystack.note(),
+            );
+        }
+    }};
+    ($haystack:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_full_match_regex [] [$haystack, $re] $($($rest)*)?)
+    };
+}
+
+/// Asserts that a byte string matches a regex using [`regex::bytes::Regex`].
+///
+/// The haystack can be anything that implements `AsRef<[u8]>`, such as a
+/// `Vec<u8>` of subprocess output, and does not need to be valid UTF-8. On
+/// failure, it is printed as an escaped `b"..."` literal.
+///
+/// [`regex::bytes::Regex`]: https://docs.rs/regex/*/regex/bytes/struct.Regex.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_matches_bytes_regex;
+/// assert_matches_bytes_regex!(b"\x00\x01frame\xFF", r"(?-u)frame\xFF");
+/// assert_matches_bytes_regex!(vec![b'o', b'k', b'\n'], r"^ok\n$");
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_matches_bytes_regex;
+/// let stdout = b"\xFE\xFFerror".to_vec();
+/// assert_matches_bytes_regex!(stdout, "^ok", "unexpected output");
+/// ```
+#[cfg(feature = "regex")]
+#[macro_export]
+macro_rules! assert_matches_bytes_regex {
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr $(,)?) => {{
         let haystack = $haystack;
-        let re = $crate::__private::regex::Regex::new(&$re).expect("a valid regex");
-        if !re.is_match(&haystack) {
+        let haystack: &[u8] = ::std::convert::AsRef::<[u8]>::as_ref(&haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] bytes; $re);
+        let re = $crate::__unwrap_regex!("assert_matches_bytes_regex", compiled);
+        if !re.is_match(haystack) {
             ::std::panic!(
-                "assertion failed: `{haystack:?}` does not match `{}`",
-                re.as_str(),
+                "assertion failed: `{}` does not match {}{}{}",
+                $crate::__private::BytesDisplay(haystack),
+                $crate::__private::Pattern(re.as_str()),
+                $crate::__private::WithOptions(compiled.options()),
+                $crate::__private::PatternLines(re.as_str()),
             );
         }
     }};
-    ($haystack:expr, $re:expr, $($arg:tt)+) => {{
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $($arg:tt)+) => {{
         let haystack = $haystack;
-        let re = $crate::__private::regex::Regex::new(&$re).expect("a valid regex");
-        if !re.is_match(&haystack) {
+        let haystack: &[u8] = ::std::convert::AsRef::<[u8]>::as_ref(&haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] bytes; $re);
+        let re = $crate::__unwrap_regex!("assert_matches_bytes_regex", compiled, $($arg)*);
+        if !re.is_match(haystack) {
             ::std::panic!(
-                "assertion failed: `{haystack:?}` does not match `{}`: {}",
-                re.as_str(),
+                "assertion failed: `{}` does not match {}{}: {}{}",
+                $crate::__private::BytesDisplay(haystack),
+                $crate::__private::Pattern(re.as_str()),
+                $crate::__private::WithOptions(compiled.options()),
                 ::std::format_args!($($arg)*),
+                $crate::__private::PatternLines(re.as_str()),
             );
         }
     }};
+    ($haystack:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_matches_bytes_regex [] [$haystack, $re] $($($rest)*)?)
+    };
 }
 
 #[cfg(test)]
 mod tests {
-    use super::assert_matches_regex;
+    #[cfg(feature = "fancy-regex")]
+    use crate::assert_matches_fancy_regex;
+    use crate::{
+        assert_all_lines_match, assert_any_line_matches, assert_debug_matches_regex,
+        assert_match_count, assert_matches_glob, assert_matches_in_order, assert_matches_template,
+        assert_no_line_matches,
+    };
+    #[cfg(feature = "regex")]
+    use crate::{assert_matches_all_regex, assert_matches_any_regex};
 
     macro_rules! assert_panic {
         ($expr:expr, $msg:expr) => {
@@ -89,6 +756,12 @@ mod tests {
         };
     }
 
+    /// The engine's error for an invalid pattern, which is worded differently
+    /// by `regex` and `regex-lite`.
+    fn regex_error(pattern: &str) -> String {
+        crate::backend::Regex::new(pattern).unwrap_err().to_string()
+    }
+
     #[test]
     fn trailing_comma() {
         assert_matches_regex!("abc", r"\w");
@@ -107,6 +780,28 @@ mod tests {
         assert_matches_regex!(String::from_utf8_lossy(b"abc"), r"\w");
     }
 
+    #[test]
+    fn literal_in_loop() {
+        for n in 0..100 {
+            assert_matches_regex!(n.to_string(), r"^\d+$");
+            assert_not_matches_regex!(n.to_string(), r"[a-z]");
+        }
+    }
+
+    #[test]
+    fn cache_per_call_site() {
+        let compiled: Vec<*const _> = (0..2)
+            .map(|_| crate::__regex!(@options [] crate::__private::Regex; r"\d") as *const _)
+            .collect();
+        assert_eq!(compiled[0], compiled[1]);
+        let fragments: Vec<*const _> = (0..2)
+            .map(|_| crate::__regex!(@options [] crate::__private::Regex; [r"\d", "x"]) as *const _)
+            .collect();
+        assert_eq!(fragments[0], fragments[1]);
+        let other: *const _ = crate::__regex!(@options [] crate::__private::Regex; r"\d");
+        assert_ne!(compiled[0], other);
+    }
+
     #[test]
     fn mismatch_no_message() {
         assert_panic!(
@@ -132,8 +827,1030 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "regex parse error")]
+    #[cfg_attr(feature = "regex", should_panic(expected = "regex parse error"))]
+    #[cfg_attr(
+        not(feature = "regex"),
+        should_panic(expected = "found unclosed character class")
+    )]
     fn bad_regex() {
-        assert_matches_regex!("abc", r"[a-z");
+        assert_matches_regex!("abc", String::from(r"[a-z"));
+    }
+
+    #[test]
+    fn not_matches() {
+        assert_not_matches_regex!("abc", r"\d");
+        assert_not_matches_regex!("abc", r"\d",);
+        assert_not_matches_regex!(String::from("abc"), String::from(r"\d"));
+        assert_not_matches_regex!("abc", r"\d", "XXX");
+    }
+
+    #[test]
+    fn not_matches_match_no_message() {
+        assert_panic!(
+            assert_not_matches_regex!("abc123", r"\d+"),
+            r#"assertion failed: `"abc123"` matches `\d+` at 3..6: `"123"`"#
+        );
+    }
+
+    #[test]
+    fn not_matches_match_message_format() {
+        assert_panic!(
+            assert_not_matches_regex!("abc123", r"\d+", "value={}", "XXX"),
+            r#"assertion failed: `"abc123"` matches `\d+` at 3..6: `"123"`: value=XXX"#
+        );
+    }
+
+    #[test]
+    fn captures() {
+        let owned = String::from("v2.13");
+        let caps = assert_captures!(owned, r"v(?<major>\d+)\.(?<minor>\d+)");
+        assert_eq!(&caps["major"], "2");
+        assert_eq!(&caps["minor"], "13");
+
+        let borrowed = "v2.13";
+        let caps = assert_captures!(borrowed, String::from(r"v(\d+)"),);
+        assert_eq!(&caps[1], "2");
+
+        let caps = assert_captures!(&owned, r"\.(\d+)", "XXX");
+        assert_eq!(&caps[1], "13");
+    }
+
+    #[test]
+    fn captures_mismatch_no_message() {
+        assert_panic!(
+            assert_captures!("abc", r"(\d)"),
+            r#"assertion failed: `"abc"` does not match `(\d)`"#
+        );
+    }
+
+    #[test]
+    fn captures_mismatch_message_format() {
+        assert_panic!(
+            assert_captures!("abc", r"(\d)", "value={}", "XXX"),
+            r#"assertion failed: `"abc"` does not match `(\d)`: value=XXX"#
+        );
+    }
+
+    #[test]
+    fn capture_expectations() {
+        let re = r"id=(?<id>\d+) user=(?<u>\w+)";
+        assert_matches_regex!("id=42 user=bob", re, id = "42", u = "bob");
+        assert_matches_regex!("id=42 user=bob", re, u = String::from("bob"),);
+    }
+
+    #[test]
+    fn capture_expectations_mismatch() {
+        assert_panic!(
+            assert_matches_regex!("id=41", r"id=(?<id>\d+)", id = "42"),
+            r#"assertion failed: `"id=41"` matches `id=(?<id>\d+)` but capture group `id` is `"41"`, expected `"42"`"#
+        );
+    }
+
+    #[test]
+    fn capture_expectations_not_participating() {
+        assert_panic!(
+            assert_matches_regex!("id=", r"id=(?<id>\d+)?", id = "42"),
+            r#"assertion failed: `"id="` matches `id=(?<id>\d+)?` but capture group `id` did not participate, expected `"42"`"#
+        );
+    }
+
+    #[test]
+    fn capture_expectations_unknown_group() {
+        assert_panic!(
+            assert_matches_regex!("id=41", r"id=(\d+)", id = "41"),
+            r#"assertion failed: `"id=41"` matches `id=(\d+)` but it has no capture group named `id`"#
+        );
+    }
+
+    #[test]
+    fn capture_expectations_no_match() {
+        assert_panic!(
+            assert_matches_regex!("user=bob", r"id=(?<id>\d+)", id = "42"),
+            r#"assertion failed: `"user=bob"` does not match `id=(?<id>\d+)`"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn full_match() {
+        assert_full_match_regex!("abc", r"\w+");
+        assert_full_match_regex!("abc", String::from(r"a|abc"),);
+        assert_full_match_regex!("", r"\w*", "XXX");
+        for n in 0..10 {
+            assert_full_match_regex!(n.to_string(), r"\d");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn full_match_partial_prefix() {
+        assert_panic!(
+            assert_full_match_regex!("abc123", r"[a-z]+"),
+            r#"assertion failed: `"abc123"` does not fully match `[a-z]+` (longest matching prefix is `"abc"`)"#
+        );
+        assert_panic!(
+            assert_full_match_regex!("xabc", r"abc"),
+            r#"assertion failed: `"xabc"` does not fully match `abc` (no prefix matches)"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn full_match_message_format() {
+        assert_panic!(
+            assert_full_match_regex!("abc123", r"[a-z]+", "value={}", "XXX"),
+            r#"assertion failed: `"abc123"` does not fully match `[a-z]+` (longest matching prefix is `"abc"`): value=XXX"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn bytes_types() {
+        assert_matches_bytes_regex!(b"abc", r"\w");
+        assert_matches_bytes_regex!(&b"abc"[..], r"\w",);
+        assert_matches_bytes_regex!(b"abc".to_vec(), String::from(r"\w"));
+        assert_matches_bytes_regex!("abc", r"\w");
+        assert_matches_bytes_regex!(String::from("abc"), r"\w", "XXX");
+        assert_matches_bytes_regex!(b"\xFF\xFE", r"(?-u)^\xFF");
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn bytes_mismatch_no_message() {
+        assert_panic!(
+            assert_matches_bytes_regex!(b"\xFFab\"c\n", r"\d"),
+            r#"assertion failed: `b"\xffab\"c\n"` does not match `\d`"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn bytes_mismatch_message_format() {
+        assert_panic!(
+            assert_matches_bytes_regex!(b"abc", r"\d", "value={}", "XXX"),
+            r#"assertion failed: `b"abc"` does not match `\d`: value=XXX"#
+        );
+    }
+
+    #[test]
+    fn mismatch_partial_match() {
+        assert_panic!(
+            assert_matches_regex!("version: 1.2.x", r"version: \d+\.\d+\.\d+"),
+            concat!(
+                r#"assertion failed: `"version: 1.2.x"` does not match `version: \d+\.\d+\.\d+`"#,
+                "\n",
+                r#"the longest matching prefix of the regex is `version: \d+\.\d+\.`, which stops here:"#,
+                "\n",
+                r#"    "version: 1.2.x""#,
+                "\n",
+                r#"                  ^"#,
+            )
+        );
+        assert_panic!(
+            assert_captures!("key=", r"key=(\w+)", "XXX"),
+            concat!(
+                r#"assertion failed: `"key="` does not match `key=(\w+)`: XXX"#,
+                "\n",
+                r#"the longest matching prefix of the regex is `key=`, which stops here:"#,
+                "\n",
+                r#"    "key=""#,
+                "\n",
+                r#"         ^"#,
+            )
+        );
+    }
+
+    #[test]
+    fn mismatch_multi_line() {
+        assert_panic!(
+            assert_matches_regex!("one\ntwo", r"\d", "XXX"),
+            "assertion failed: haystack does not match `\\d`: XXX\n1 | one\n2 | two"
+        );
+    }
+
+    #[test]
+    fn bad_regex_message() {
+        let line = line!() + 2;
+        assert_panic!(
+            assert_matches_regex!("abc", String::from(r"[a-z")),
+            format!(
+                "invalid regex in assert_matches_regex! at {}:{line}:13\n\
+                 pattern: `[a-z`\n\
+                 {}",
+                file!(),
+                regex_error("[a-z"),
+            )
+        );
+    }
+
+    #[test]
+    fn bad_regex_message_format() {
+        let line = line!() + 2;
+        assert_panic!(
+            assert_not_matches_regex!("abc", String::from(r"(a"), "value={}", "XXX"),
+            format!(
+                "invalid regex in assert_not_matches_regex! at {}:{line}:13\n\
+                 pattern: `(a`\n\
+                 message: value=XXX\n\
+                 {}",
+                file!(),
+                regex_error("(a"),
+            )
+        );
+    }
+
+    #[test]
+    fn lines() {
+        let log = "[INFO] a\r\n[WARN] b\n";
+        assert_all_lines_match!(log, r"^\[\w+\] \w$");
+        assert_all_lines_match!("", r"\d",);
+        assert_any_line_matches!(log, r"^\[WARN\]");
+        assert_any_line_matches!(String::from(log), String::from("b$"), "XXX");
+        assert_no_line_matches!(log, r"ERROR");
+        assert_no_line_matches!(log, r"\n", "XXX");
+        assert_all_lines_match!(log, r"^\[[a-z]+\]", case_insensitive);
+        assert_any_line_matches!(log, "warn", case_insensitive, "XXX");
+        assert_no_line_matches!(log, "^b", multi_line(false));
+    }
+
+    #[test]
+    fn lines_with_options() {
+        assert_panic!(
+            assert_all_lines_match!("A\nb", "a", case_insensitive),
+            "assertion failed: not every line matches `a` with options `case_insensitive`\n2 | b"
+        );
+        assert_panic!(
+            assert_no_line_matches!("A\nb", "a", case_insensitive, "value={}", "XXX"),
+            "assertion failed: some lines match `a` with options `case_insensitive`: value=XXX\n1 | A"
+        );
+    }
+
+    #[test]
+    fn all_lines_mismatch() {
+        assert_panic!(
+            assert_all_lines_match!("[INFO] a\nb\n[INFO] c\n\td", r"^\["),
+            "assertion failed: not every line matches `^\\[`\n2 | b\n4 | \\td"
+        );
+        assert_panic!(
+            assert_all_lines_match!("a\n".repeat(10), r"b", "value={}", "XXX"),
+            format!(
+                "assertion failed: not every line matches `b`: value=XXX{}",
+                (1..=10)
+                    .map(|n| format!("\n{n:>2} | a"))
+                    .collect::<String>(),
+            )
+        );
+    }
+
+    #[test]
+    fn any_line_mismatch() {
+        assert_panic!(
+            assert_any_line_matches!("a\nb", r"^c$"),
+            "assertion failed: no line matches `^c$`\n1 | a\n2 | b"
+        );
+        assert_panic!(
+            assert_any_line_matches!("", r"^c$", "value={}", "XXX"),
+            "assertion failed: no line matches `^c$`: value=XXX"
+        );
+    }
+
+    #[test]
+    fn no_line_mismatch() {
+        assert_panic!(
+            assert_no_line_matches!("a\nb\nab", r"b"),
+            "assertion failed: some lines match `b`\n2 | b\n3 | ab"
+        );
+    }
+
+    #[test]
+    fn in_order() {
+        let log = "started\nconnected\nshutdown";
+        assert_matches_in_order!(log, ["started", "connected", "shutdown"]);
+        assert_matches_in_order!(log, [r"\w+", String::from("shut"),],);
+        assert_matches_in_order!(log, ["t", "t", "t", "t"], "XXX");
+        assert_matches_in_order!("aa", ["a", "", "a", ""]);
+    }
+
+    #[test]
+    fn in_order_mismatch() {
+        assert_panic!(
+            assert_matches_in_order!("connected started", ["started", "connected"]),
+            concat!(
+                r#"assertion failed: `"connected started"` does not match `connected` (pattern 2 of 2) after byte 17, where `started` (pattern 1) ended"#,
+                "\n",
+                r#"    "connected started""#,
+                "\n",
+                r#"                      ^"#,
+            )
+        );
+        assert_panic!(
+            assert_matches_in_order!("a\nb", ["c", "a"], "value={}", "XXX"),
+            "assertion failed: haystack does not match `c` (pattern 1 of 2): value=XXX\n1 | a\n2 | b"
+        );
+    }
+
+    #[test]
+    fn in_order_bad_regex() {
+        let line = line!() + 2;
+        assert_panic!(
+            assert_matches_in_order!("abc", ["a", String::from("(")]),
+            format!(
+                "invalid regex in assert_matches_in_order! at {}:{line}:13\n\
+                 pattern: `(`\n\
+                 {}",
+                file!(),
+                regex_error("("),
+            )
+        );
+    }
+
+    #[test]
+    fn match_count() {
+        let output = "warn: a\nretry\nwarn: b\nwarn: c";
+        assert_match_count!(output, "warn", 3);
+        assert_match_count!(output, "error", 0);
+        assert_match_count!(output, "retry", 1..);
+        assert_match_count!(output, "retry", ..=2);
+        assert_match_count!(output, String::from("warn"), 2..4, "XXX");
+        assert_match_count!(output, r"\w+", 7..=7);
+        assert_match_count!(output, "error", ..1);
+        assert_match_count!(output, "^WARN", 3, case_insensitive, multi_line);
+        assert_match_count!(output, "RETRY", 1, case_insensitive, "XXX");
+    }
+
+    #[test]
+    fn match_count_mismatch() {
+        assert_panic!(
+            assert_match_count!("a1b22", r"\d+", 3),
+            concat!(
+                r#"assertion failed: `"a1b22"` matches `\d+` 2 times, expected exactly 3"#,
+                "\nmatches:",
+                "\n    1..2: `\"1\"`",
+                "\n    3..5: `\"22\"`",
+            )
+        );
+        assert_panic!(
+            assert_match_count!("a\nb", "retry", 1.., "value={}", "XXX"),
+            "assertion failed: haystack matches `retry` 0 times, expected at least 1: value=XXX\n1 | a\n2 | b"
+        );
+        assert_panic!(
+            assert_match_count!("aA", "a", 1, case_insensitive, "XXX"),
+            concat!(
+                r#"assertion failed: `"aA"` matches `a` with options `case_insensitive` 2 times, expected exactly 1: XXX"#,
+                "\nmatches:",
+                "\n    0..1: `\"a\"`",
+                "\n    1..2: `\"A\"`",
+            )
+        );
+        assert_panic!(
+            assert_match_count!("aaa", "a", ..=2),
+            concat!(
+                r#"assertion failed: `"aaa"` matches `a` 3 times, expected at most 2"#,
+                "\nmatches:",
+                "\n    0..1: `\"a\"`",
+                "\n    1..2: `\"a\"`",
+                "\n    2..3: `\"a\"`",
+            )
+        );
+        assert_panic!(
+            assert_match_count!("a", "a", 2..4),
+            "assertion failed: `\"a\"` matches `a` 1 time, expected 2..4\nmatches:\n    0..1: `\"a\"`"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn matches_all() {
+        let err = "error[E0308]: mismatched types";
+        assert_matches_all_regex!(err, ["error", r"E\d+", "types"]);
+        assert_matches_all_regex!(err, ["error", String::from("types"),],);
+        assert_matches_all_regex!(err, ["mismatched"], "value={}", "XXX");
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn matches_all_mismatch() {
+        assert_panic!(
+            assert_matches_all_regex!("error: bad", ["error", "found", r"E\d+"]),
+            concat!(
+                r#"assertion failed: `"error: bad"` does not match 2 of 3 regexes"#,
+                "\n    `found`",
+                "\n    `E\\d+`",
+            )
+        );
+        assert_panic!(
+            assert_matches_all_regex!("a\nb", ["a", "c"], "value={}", "XXX"),
+            "assertion failed: haystack does not match 1 of 2 regexes: value=XXX\n    `c`\n1 | a\n2 | b"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn matches_any() {
+        assert_matches_any_regex!("connection reset", ["timed out", "reset"]);
+        assert_matches_any_regex!("timed out", [String::from("timed out"), "reset"]);
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn matches_any_mismatch() {
+        assert_panic!(
+            assert_matches_any_regex!("ok", ["timed out", "reset"], "value={}", "XXX"),
+            concat!(
+                r#"assertion failed: `"ok"` does not match any of 2 regexes: value=XXX"#,
+                "\n    `timed out`",
+                "\n    `reset`",
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn matches_all_bad_regex() {
+        let line = line!() + 2;
+        assert_panic!(
+            assert_matches_all_regex!("abc", ["a", String::from("(")]),
+            format!(
+                "invalid regex in assert_matches_all_regex! at {}:{line}:13\n\
+                 pattern: `(`\n\
+                 {}",
+                file!(),
+                regex_error("("),
+            )
+        );
+    }
+
+    #[test]
+    fn fragments() {
+        assert_matches_regex!(
+            "key = 42",
+            [r"^(?<key>\w+)  # the key", r"\s*=\s*", r"\d+$"]
+        );
+        assert_matches_regex!(
+            "key = 42",
+            ["(?<key>key)", r"\ =\ ", r"(?<v>\d+)"],
+            v = "42"
+        );
+        let value = String::from(r"\d+  # the value");
+        assert_matches_regex!("key = 42", [String::from(r"key\s*=\s*"), value]);
+        #[cfg(feature = "regex")]
+        assert_matches_bytes_regex!(b"\xFFkey", [r"(?-u)\xFF", "key"]);
+        assert_not_matches_regex!("key = 42", ["key", "  # not the value", r"\s* : "]);
+        assert_match_count!("a1 b2 c3", [r"\w", r"\d  # digit"], 3);
+    }
+
+    #[test]
+    fn fragments_mismatch() {
+        assert_panic!(
+            assert_matches_regex!("key = x", [r"(?<key>\w+)  # the key", r"\s*=\s*", r"\d+"]),
+            concat!(
+                r#"assertion failed: `"key = x"` does not match the regex"#,
+                "\n    (?x)",
+                r"
+    (?<key>\w+)  # the key",
+                r"
+    \s*=\s*",
+                r"
+    \d+",
+                "\nthe longest matching prefix of the regex ends with `\\s*=\\s*`, which stops here:",
+                "\n    \"key = x\"",
+                "\n           ^",
+            )
+        );
+    }
+
+    #[test]
+    fn fragments_other_macros() {
+        assert_panic!(
+            assert_not_matches_regex!("key=a", ["key", "="]),
+            r#"assertion failed: `"key=a"` matches the regex at 0..4: `"key="`
+    (?x)
+    key
+    ="#
+        );
+        assert_panic!(
+            assert_match_count!("ab", ["a", "b"], 2),
+            r#"assertion failed: `"ab"` matches the regex 1 time, expected exactly 2
+    (?x)
+    a
+    b
+matches:
+    0..2: `"ab"`"#
+        );
+        assert_panic!(
+            assert_all_lines_match!("ab\ncd", ["a", "b"], "XXX"),
+            "assertion failed: not every line matches the regex: XXX
+    (?x)
+    a
+    b
+2 | cd"
+        );
+        #[cfg(feature = "regex")]
+        assert_panic!(
+            assert_full_match_regex!("abc", ["a", "b"]),
+            "assertion failed: `\"abc\"` does not fully match the regex \
+             (longest matching prefix is `\"ab\"`)
+    (?x)
+    a
+    b"
+        );
+        #[cfg(feature = "regex")]
+        assert_panic!(
+            assert_matches_bytes_regex!(b"ac", ["a", "b"]),
+            r#"assertion failed: `b"ac"` does not match the regex
+    (?x)
+    a
+    b"#
+        );
+    }
+
+    #[test]
+    fn fragments_bad_regex() {
+        let line = line!() + 2;
+        assert_panic!(
+            assert_matches_regex!("abc", [String::from("a"), String::from("(")]),
+            format!(
+                "invalid regex in assert_matches_regex! at {}:{line}:13\n\
+                 pattern:\n    (?x)\n    a\n    (\n\
+                 {}",
+                file!(),
+                regex_error("(?x)\na\n("),
+            )
+        );
+    }
+
+    #[test]
+    fn options() {
+        assert_matches_regex!("HELLO", "hello", case_insensitive);
+        assert_matches_regex!("a\r\nb\r\n", "^b$", multi_line, crlf,);
+        assert_matches_regex!("a\nb", "a.b", dot_matches_new_line(true), "XXX");
+        assert_matches_regex!("id=A1", r"id=(?<id>[a-z]\d)", case_insensitive, id = "A1");
+        assert_matches_regex!("key=42", ["key = ", r"\d+"], case_insensitive(false));
+        assert_matches_regex!(
+            "abc",
+            String::from("B"),
+            case_insensitive,
+            size_limit(1 << 20)
+        );
+        assert_not_matches_regex!("HELLO", "hello", case_insensitive(false));
+        let caps = assert_captures!("HELLO", "(?<h>h)", case_insensitive);
+        assert_eq!(&caps["h"], "H");
+        #[cfg(feature = "regex")]
+        assert_full_match_regex!("ABC", "[a-c]+", case_insensitive, "XXX");
+        #[cfg(feature = "regex")]
+        assert_matches_bytes_regex!(b"\xFF", r"\xFF", unicode(false));
+    }
+
+    #[test]
+    fn options_mismatch() {
+        #[cfg(feature = "regex")]
+        assert_panic!(
+            assert_matches_regex!("abc", "B", unicode(false), "value={}", "XXX"),
+            r#"assertion failed: `"abc"` does not match `B` with options `unicode(false)`: value=XXX"#
+        );
+        assert_panic!(
+            assert_not_matches_regex!("HELLO", "hello", case_insensitive),
+            r#"assertion failed: `"HELLO"` matches `hello` with options `case_insensitive` at 0..5: `"HELLO"`"#
+        );
+        #[cfg(feature = "regex")]
+        assert_panic!(
+            assert_full_match_regex!("ab", "a", multi_line, crlf),
+            r#"assertion failed: `"ab"` does not fully match `a` with options `multi_line, crlf` (longest matching prefix is `"a"`)"#
+        );
+        #[cfg(feature = "regex")]
+        assert_panic!(
+            assert_matches_bytes_regex!(b"A", "b", case_insensitive),
+            r#"assertion failed: `b"A"` does not match `b` with options `case_insensitive`"#
+        );
+    }
+
+    #[test]
+    fn options_bad_regex() {
+        let line = line!() + 2;
+        assert_panic!(
+            assert_matches_regex!("abc", r"\w{100}", size_limit(100)),
+            format!(
+                "invalid regex in assert_matches_regex! at {}:{line}:13\n\
+                 pattern: `\\w{{100}}`\n\
+                 options: `size_limit(100)`\n\
+                 {}",
+                file!(),
+                crate::__private::Options::new()
+                    .size_limit(100)
+                    .build(r"\w{100}")
+                    .unwrap_err(),
+            )
+        );
+    }
+
+    #[test]
+    fn compiled_regex() {
+        use crate::backend::Regex;
+        use std::sync::LazyLock;
+
+        static HEX: LazyLock<Regex> = LazyLock::new(|| Regex::new("^[a-f0-9]+$").unwrap());
+        let re = Regex::new(r"\d+").unwrap();
+        assert_matches_regex!("deadbeef", HEX);
+        assert_matches_regex!("deadbeef", &*HEX, "XXX");
+        assert_matches_regex!("42", re);
+        assert_matches_regex!("42", &re);
+        assert_matches_regex!("42", re.clone());
+        assert_not_matches_regex!("xyz", HEX);
+        assert_all_lines_match!("1\n2\n", re);
+        assert_match_count!("1 2 3", re, 3);
+        assert_matches_in_order!("1 a 2", [&re, "a", re.clone()]);
+        #[cfg(feature = "regex")]
+        {
+            let bytes = regex::bytes::Regex::new(r"(?-u)\xFF").unwrap();
+            assert_matches_bytes_regex!(b"\xFF", bytes);
+        }
+        assert_panic!(
+            assert_matches_regex!("xyz", HEX),
+            r#"assertion failed: `"xyz"` does not match `^[a-f0-9]+$`"#
+        );
+    }
+
+    #[test]
+    fn compiled_regex_with_options() {
+        let re = crate::backend::Regex::new("a").unwrap();
+        let line = line!() + 2;
+        assert_panic!(
+            assert_matches_regex!("A", re, case_insensitive),
+            format!(
+                "options can't be applied to an already compiled regex in \
+                 assert_matches_regex! at {}:{line}:13\n\
+                 pattern: `a`\n\
+                 options: `case_insensitive`",
+                file!(),
+            )
+        );
+        let line = line!() + 2;
+        assert_panic!(
+            assert_debug_matches_regex!("A", &re, case_insensitive, "XXX"),
+            format!(
+                "options can't be applied to an already compiled regex in \
+                 assert_debug_matches_regex! at {}:{line}:13\n\
+                 pattern: `a`\n\
+                 options: `case_insensitive`\n\
+                 message: XXX",
+                file!(),
+            )
+        );
+    }
+
+    #[test]
+    fn haystack_types() {
+        use std::ffi::{OsStr, OsString};
+        use std::path::{Path, PathBuf};
+
+        assert_matches_regex!(Path::new("src/lib.rs"), r"\.rs$");
+        assert_matches_regex!(PathBuf::from("src/lib.rs"), "^src/");
+        assert_matches_regex!(OsStr::new("abc"), "b");
+        assert_matches_regex!(OsString::from("abc"), "b", "XXX");
+        assert_matches_regex!(format_args!("{}-{}", 1, 2), r"^\d-\d$");
+        assert_matches_regex!(std::borrow::Cow::Borrowed("abc"), "c");
+        assert_not_matches_regex!(Path::new("a/b"), r"\\");
+        assert_all_lines_match!(OsStr::new("a1\nb2"), r"\d");
+        assert_match_count!(Path::new("a/b/c"), "/", 2);
+        #[cfg(feature = "regex")]
+        assert_full_match_regex!(Path::new("a/b"), "[a-z/]+");
+        assert_panic!(
+            assert_matches_regex!(Path::new("a/b"), r"\d"),
+            r#"assertion failed: `"a/b"` does not match `\d`"#
+        );
+    }
+
+    #[test]
+    fn custom_haystack() {
+        use crate::Haystack;
+        use std::borrow::Cow;
+
+        struct Version(u32, u32);
+
+        impl Haystack for Version {
+            fn to_haystack(&self) -> Cow<'_, str> {
+                Cow::Owned(format!("v{}.{}", self.0, self.1))
+            }
+        }
+
+        assert_matches_regex!(Version(1, 80), r"^v1\.80$");
+        assert_matches_regex!(&Version(1, 80), "80");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lossy_haystack() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let os = OsStr::from_bytes(b"a\xFFb");
+        assert_matches_regex!(os, "^a\u{FFFD}b$");
+        assert_panic!(
+            assert_matches_regex!(os, "c"),
+            concat!(
+                "assertion failed: `\"a\u{FFFD}b\"` does not match `c`\n",
+                "note: the haystack is not valid UTF-8, so it was converted lossily, ",
+                "replacing invalid sequences with U+FFFD",
+            )
+        );
+        assert_panic!(
+            assert_not_matches_regex!(os, "b", "XXX"),
+            concat!(
+                "assertion failed: `\"a\u{FFFD}b\"` matches `b` at 4..5: `\"b\"`: XXX\n",
+                "note: the haystack is not valid UTF-8, so it was converted lossily, ",
+                "replacing invalid sequences with U+FFFD",
+            )
+        );
+    }
+
+    #[test]
+    fn debug() {
+        assert_debug_matches_regex!(vec![1, 2, 3], r"^\[1, 2, 3\]$");
+        assert_debug_matches_regex!(Some("a"), r#"Some\("a"\)"#, "XXX");
+        assert_debug_matches_regex!(Some('A'), "some", case_insensitive);
+        assert_debug_matches_regex!("{:?}", 12, r"^\d+$");
+        assert_debug_matches_regex!("{:#?}", (1, "a"), r#"(?m)^    "a",$"#);
+        assert_debug_matches_regex!("{:#?}", [1], r"\[\n    1,\n\]", "value={}", "XXX");
+        assert_debug_matches_regex!(
+            "{:#?}",
+            Some('A'),
+            r"^some\($",
+            case_insensitive,
+            multi_line
+        );
+    }
+
+    #[test]
+    fn debug_mismatch() {
+        assert_panic!(
+            assert_debug_matches_regex!(vec![1, 2, 3], "4"),
+            r#"assertion failed: `{:?}` of `vec![1, 2, 3]` does not match `4`
+    "[1, 2, 3]""#
+        );
+        assert_panic!(
+            assert_debug_matches_regex!(Some(1), r"Some\(2\)", "value={}", "XXX"),
+            r#"assertion failed: `{:?}` of `Some(1)` does not match `Some\(2\)`: value=XXX
+the longest matching prefix of the regex is `Some\(`, which stops here:
+    "Some(1)"
+          ^"#
+        );
+        assert_panic!(
+            assert_debug_matches_regex!("{:?}", String::new(), r"^\d+$"),
+            r#"assertion failed: `{:?}` of `String::new()` does not match `^\d+$`
+    "\"\"""#
+        );
+        assert_panic!(
+            assert_debug_matches_regex!("{:#?}", (1, 2), "3"),
+            "assertion failed: `{:#?}` of `(1, 2)` does not match `3`\n\
+             1 | (\n\
+             2 |     1,\n\
+             3 |     2,\n\
+             4 | )"
+        );
+    }
+
+    #[test]
+    fn debug_assert() {
+        debug_assert_matches_regex!("abc_1", "^[a-z_][a-z0-9_]*$");
+        debug_assert_matches_regex!("ABC", "^abc$", case_insensitive, "XXX");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn debug_assert_mismatch() {
+        assert_panic!(
+            debug_assert_matches_regex!("1abc", "^[a-z_]", "XXX"),
+            r#"assertion failed: `"1abc"` does not match `^[a-z_]`: XXX"#
+        );
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn debug_assert_release() {
+        let mut evaluated = false;
+        debug_assert_matches_regex!(
+            {
+                evaluated = true;
+                "1abc"
+            },
+            "^[a-z_]"
+        );
+        assert!(!evaluated);
+    }
+
+    #[test]
+    fn glob() {
+        assert_matches_glob!("error: file.txt not found", "error: * not found");
+        assert_matches_glob!("a.b (c)", "?.? (*)");
+        assert_matches_glob!("line 1\nline 2", "line 1*2");
+        assert_matches_glob!("x7", "[a-z][!a-z]", "XXX");
+        assert_matches_glob!("Error: *", "error: [*]", case_insensitive);
+        assert_matches_glob!("abc", String::from("a*"));
+    }
+
+    #[test]
+    fn glob_mismatch() {
+        assert_panic!(
+            assert_matches_glob!("error: file.txt is missing", "error: * not found"),
+            r#"assertion failed: `"error: file.txt is missing"` does not match glob `error: * not found`
+regex: `(?s)^error: .* not found$`
+the longest matching prefix of the regex is `(?s)^error: .* `, which stops here:
+    "error: file.txt is missing"
+                        ^"#
+        );
+        assert_panic!(
+            assert_matches_glob!("ab", "a", case_insensitive, "XXX"),
+            r#"assertion failed: `"ab"` does not match glob `a` with options `case_insensitive`: XXX
+regex: `(?s)^a$`
+the longest matching prefix of the regex is `(?s)^a`, which stops here:
+    "ab"
+      ^"#
+        );
+        assert_panic!(
+            assert_matches_glob!("one\ntwo\n", "one\nthree\n"),
+            "assertion failed: haystack does not match the glob\n    \
+             one\n    \
+             three\n\
+             regex: `(?s)^one\\nthree\\n$`\n\
+             the longest matching prefix of the regex is `(?s)^one\\nt`, which stops here:\n\
+             1 | one\n\
+             2 | two\n  \
+             |  ^\n\
+             3 | "
+        );
+    }
+
+    #[test]
+    fn glob_bad_range() {
+        let line = line!() + 2;
+        assert_panic!(
+            assert_matches_glob!("b", "[z-a]"),
+            format!(
+                "invalid regex in assert_matches_glob! at {}:{line}:13\n\
+                 pattern: `(?s)^[z-a]$`\n\
+                 {}",
+                file!(),
+                regex_error("(?s)^[z-a]$"),
+            )
+        );
+    }
+
+    #[test]
+    fn template() {
+        assert_matches_template!(
+            "Finished in 1.52s with 12 tests, id 67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "Finished in {duration} with {int} tests, id {uuid}",
+        );
+        assert_matches_template!("{a} (b)", "{{{word}}} ({any})");
+        assert_matches_template!("V1.2.3", "v{semver}", case_insensitive, "XXX");
+        assert_matches_template!("1\n2", String::from("{int}\n{int}"));
+    }
+
+    #[test]
+    fn template_registered() {
+        crate::register_placeholder("test_level", "INFO|WARN|ERROR");
+        assert_matches_template!("[WARN] low disk", "[{test_level}] {any}");
+        assert_panic!(
+            assert_matches_template!("[DEBUG] x", "[{test_level}] {any}"),
+            r#"assertion failed: `"[DEBUG] x"` does not match template `[{test_level}] {any}`
+regex: `^\[(?:INFO|WARN|ERROR)\] (?:.*)$`
+the longest matching prefix of the regex is `^\[`, which stops here:
+    "[DEBUG] x"
+      ^"#
+        );
+    }
+
+    #[test]
+    fn template_mismatch() {
+        assert_panic!(
+            assert_matches_template!("took 5 ms", "took {duration}"),
+            r#"assertion failed: `"took 5 ms"` does not match template `took {duration}`
+regex: `^took (?:\d+(?:\.\d+)?(?:ns|[µu]s|ms|s|m|h))$`
+the longest matching prefix of the regex is `^took `, which stops here:
+    "took 5 ms"
+          ^"#
+        );
+        assert_panic!(
+            assert_matches_template!("id: zz", "id: {hex}", "value={}", "XXX"),
+            r#"assertion failed: `"id: zz"` does not match template `id: {hex}`: value=XXX
+regex: `^id: (?:(?:0[xX])?[0-9a-fA-F]+)$`
+the longest matching prefix of the regex is `^id: `, which stops here:
+    "id: zz"
+         ^"#
+        );
+    }
+
+    #[test]
+    fn bad_template() {
+        let line = line!() + 2;
+        assert_panic!(
+            assert_matches_template!("abc", "{nope}"),
+            format!(
+                "invalid template in assert_matches_template! at {}:{line}:13\n\
+                 template: `{{nope}}`\n\
+                 unknown placeholder `{{nope}}`; register it with `register_placeholder`",
+                file!(),
+            )
+        );
+        let line = line!() + 2;
+        assert_panic!(
+            assert_matches_template!("abc", "a{int", "value={}", "XXX"),
+            format!(
+                "invalid template in assert_matches_template! at {}:{line}:13\n\
+                 template: `a{{int`\n\
+                 message: value=XXX\n\
+                 unclosed `{{`; write `{{{{` for a literal one",
+                file!(),
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid regex for placeholder `{test_bad}`: `(a`")]
+    fn register_bad_placeholder() {
+        crate::register_placeholder("test_bad", "(a");
+    }
+
+    #[test]
+    #[cfg(feature = "fancy-regex")]
+    fn fancy() {
+        assert_matches_fancy_regex!("foobaz", "foo(?!bar)");
+        assert_matches_fancy_regex!(String::from("<b>x</b>"), r"<(\w+)>.*</\1>", "XXX");
+        assert_matches_fancy_regex!("FOO", "(?<=^)foo", case_insensitive);
+        assert_matches_fancy_regex!("abab", ["(ab)", r"\1  # again"]);
+        let re = fancy_regex::Regex::new(r"(\w)\1").unwrap();
+        assert_matches_fancy_regex!("hello", re);
+        assert_matches_fancy_regex!("hello", &re, "value={}", "XXX");
+    }
+
+    #[test]
+    #[cfg(feature = "fancy-regex")]
+    fn fancy_mismatch() {
+        assert_panic!(
+            assert_matches_fancy_regex!("foobar", "foo(?!bar)"),
+            r#"assertion failed: `"foobar"` does not match `foo(?!bar)`"#
+        );
+        assert_panic!(
+            assert_matches_fancy_regex!("a\nb", r"(\w)\n\1", "value={}", "XXX"),
+            "assertion failed: haystack does not match `(\\w)\\n\\1`: value=XXX\n\
+             1 | a\n\
+             2 | b"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "fancy-regex")]
+    fn fancy_backtrack_limit() {
+        let haystack = format!("{}b", "a".repeat(20));
+        assert_panic!(
+            assert_matches_fancy_regex!(&haystack, r"^(a|aa)+\1?$", backtrack_limit(100)),
+            format!(
+                "assertion failed: `{haystack:?}` could not be matched against `^(a|aa)+\\1?$` \
+                 with options `backtrack_limit(100)`\n\
+                 Error executing regex: Max limit for backtracking count exceeded",
+            )
+        );
+        assert_panic!(
+            assert_matches_fancy_regex!(
+                "aaaaaaaaaa\nb",
+                [r"^(a|aa)+", r"\1?$"],
+                backtrack_limit(100),
+                "value={}",
+                "XXX"
+            ),
+            "assertion failed: haystack could not be matched against the regex \
+             with options `backtrack_limit(100)`: value=XXX\n    (?x)\n    ^(a|aa)+\n    \\1?$\n\
+             Error executing regex: Max limit for backtracking count exceeded\n\
+             1 | aaaaaaaaaa\n\
+             2 | b"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "fancy-regex")]
+    fn fancy_bad_regex() {
+        let line = line!() + 2;
+        assert_panic!(
+            assert_matches_fancy_regex!("abc", String::from(r"(a"), "value={}", "XXX"),
+            format!(
+                "invalid regex in assert_matches_fancy_regex! at {}:{line}:13\n\
+                 pattern: `(a`\n\
+                 message: value=XXX\n\
+                 {}",
+                file!(),
+                fancy_regex::Regex::new("(a").unwrap_err(),
+            )
+        );
+        let line = line!() + 2;
+        assert_panic!(
+            assert_matches_fancy_regex!("abc", "a+", swap_greed),
+            format!(
+                "invalid regex in assert_matches_fancy_regex! at {}:{line}:13\n\
+                 pattern: `a+`\n\
+                 options: `swap_greed`\n\
+                 the `swap_greed` option is not supported by fancy-regex",
+                file!(),
+            )
+        );
     }
 }
@@ -0,0 +1,231 @@
+//! Assertions about the individual lines of a haystack.
+
+use crate::backend::Regex;
+use crate::haystack::Text;
+use crate::options::Options;
+use crate::render::{NumberedLines, Pattern, PatternLines, WithOptions};
+use std::fmt;
+
+/// Which lines of the haystack must match.
+pub enum Lines {
+    /// Every line must match.
+    All,
+    /// At least one line must match.
+    Any,
+    /// No line may match.
+    None,
+}
+
+/// Panics unless the lines of `haystack` match `re`, which was compiled with
+/// `options`, as `lines` requires, listing the offending lines.
+#[track_caller]
+pub fn assert_lines(
+    haystack: Text<'_>,
+    re: &Regex,
+    options: &Options,
+    lines: Lines,
+    args: Option<fmt::Arguments<'_>>,
+) {
+    let note = haystack.note();
+    let haystack = haystack.as_str();
+    let numbered = haystack.lines().enumerate().map(|(i, line)| (i + 1, line));
+    let (summary, offending) = match lines {
+        Lines::All => {
+            let offending: Vec<_> = numbered.filter(|(_, line)| !re.is_match(line)).collect();
+            if offending.is_empty() {
+                return;
+            }
+            ("not every line matches", offending)
+        }
+        Lines::Any => {
+            if haystack.lines().any(|line| re.is_match(line)) {
+                return;
+            }
+            ("no line matches", numbered.collect())
+        }
+        Lines::None => {
+            let offending: Vec<_> = numbered.filter(|(_, line)| re.is_match(line)).collect();
+            if offending.is_empty() {
+                return;
+            }
+            ("some lines match", offending)
+        }
+    };
+    let summary = format!("{summary} {}{}", Pattern(re.as_str()), WithOptions(options));
+    let details = format!("{}{}", PatternLines(re.as_str()), NumberedLines(offending));
+    match args {
+        Some(args) => panic!("assertion failed: {summary}: {args}{details}{note}"),
+        None => panic!("assertion failed: {summary}{details}{note}"),
+    }
+}
+
+/// Asserts that every line of a string matches a regex using
+/// [`regex::Regex`].
+///
+/// The string is split with [`str::lines`], so a trailing newline does not
+/// count as an extra, empty line. On failure, the panic message lists each
+/// line that does not match, along with its line number.
+///
+/// [`regex::Regex`]: https://docs.rs/regex/*/regex/struct.Regex.html
+/// [`str::lines`]: https://doc.rust-lang.org/std/primitive.str.html#method.lines
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_all_lines_match;
+/// let log = "[INFO] starting\n[WARN] low disk\n[INFO] done\n";
+/// assert_all_lines_match!(log, r"^\[\w+\] ");
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_all_lines_match;
+/// let log = "[INFO] starting\npanicked at src/main.rs\n";
+/// assert_all_lines_match!(log, r"^\[\w+\] ", "unexpected log output");
+/// ```
+#[macro_export]
+macro_rules! assert_all_lines_match {
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $re);
+        let re = $crate::__unwrap_regex!("assert_all_lines_match", compiled);
+        $crate::__private::assert_lines(
+            haystack,
+            re,
+            compiled.options(),
+            $crate::__private::Lines::All,
+            ::std::option::Option::None,
+        );
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $re);
+        let re = $crate::__unwrap_regex!("assert_all_lines_match", compiled, $($arg)*);
+        $crate::__private::assert_lines(
+            haystack,
+            re,
+            compiled.options(),
+            $crate::__private::Lines::All,
+            ::std::option::Option::Some(::std::format_args!($($arg)*)),
+        );
+    }};
+    ($haystack:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_all_lines_match [] [$haystack, $re] $($($rest)*)?)
+    };
+}
+
+/// Asserts that at least one line of a string matches a regex using
+/// [`regex::Regex`].
+///
+/// Unlike [`assert_matches_regex!`], the regex is run against each line on
+/// its own, so `^` and `$` anchor to the line. On failure, the panic message
+/// lists every line, along with its line number.
+///
+/// [`regex::Regex`]: https://docs.rs/regex/*/regex/struct.Regex.html
+/// [`assert_matches_regex!`]: macro.assert_matches_regex.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_any_line_matches;
+/// let output = "Compiling foo\nFinished dev profile\n";
+/// assert_any_line_matches!(output, r"^Finished ");
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_any_line_matches;
+/// let output = "Compiling foo\nerror: could not compile\n";
+/// assert_any_line_matches!(output, r"^Finished ", "build did not finish");
+/// ```
+#[macro_export]
+macro_rules! assert_any_line_matches {
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $re);
+        let re = $crate::__unwrap_regex!("assert_any_line_matches", compiled);
+        $crate::__private::assert_lines(
+            haystack,
+            re,
+            compiled.options(),
+            $crate::__private::Lines::Any,
+            ::std::option::Option::None,
+        );
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $re);
+        let re = $crate::__unwrap_regex!("assert_any_line_matches", compiled, $($arg)*);
+        $crate::__private::assert_lines(
+            haystack,
+            re,
+            compiled.options(),
+            $crate::__private::Lines::Any,
+            ::std::option::Option::Some(::std::format_args!($($arg)*)),
+        );
+    }};
+    ($haystack:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_any_line_matches [] [$haystack, $re] $($($rest)*)?)
+    };
+}
+
+/// Asserts that no line of a string matches a regex using [`regex::Regex`].
+///
+/// On failure, the panic message lists each line that matches, along with its
+/// line number.
+///
+/// [`regex::Regex`]: https://docs.rs/regex/*/regex/struct.Regex.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_no_line_matches;
+/// let log = "[INFO] starting\n[INFO] done\n";
+/// assert_no_line_matches!(log, r"^\[(WARN|ERROR)\]");
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_no_line_matches;
+/// let log = "[INFO] starting\n[ERROR] disk full\n";
+/// assert_no_line_matches!(log, r"^\[ERROR\]", "errors were logged");
+/// ```
+#[macro_export]
+macro_rules! assert_no_line_matches {
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $re);
+        let re = $crate::__unwrap_regex!("assert_no_line_matches", compiled);
+        $crate::__private::assert_lines(
+            haystack,
+            re,
+            compiled.options(),
+            $crate::__private::Lines::None,
+            ::std::option::Option::None,
+        );
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $re:expr, $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let compiled = $crate::__regex!(@options [$($opt)*] $re);
+        let re = $crate::__unwrap_regex!("assert_no_line_matches", compiled, $($arg)*);
+        $crate::__private::assert_lines(
+            haystack,
+            re,
+            compiled.options(),
+            $crate::__private::Lines::None,
+            ::std::option::Option::Some(::std::format_args!($($arg)*)),
+        );
+    }};
+    ($haystack:expr, $re:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_no_line_matches [] [$haystack, $re] $($($rest)*)?)
+    };
+}
@@ -0,0 +1,344 @@
+//! Options for compiling a regex, as with `regex::RegexBuilder`.
+
+use crate::backend::{Error, Regex, RegexBuilder};
+#[cfg(feature = "fancy-regex")]
+use crate::compile::CompileError;
+#[cfg(feature = "regex")]
+use regex_automata::{meta, util::syntax};
+use std::fmt;
+
+/// Options set after the pattern in an assertion macro, such as
+/// `case_insensitive` or `size_limit(1 << 20)`. Each option is a method named
+/// after the `regex::RegexBuilder` method that it calls. They are kept in
+/// the order they were given, so that they can be echoed in panic messages.
+///
+/// `regex-lite` has no `line_terminator`, `unicode`, `octal`, or
+/// `dfa_size_limit`, so those options only exist with the `regex` feature.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Options(Vec<Opt>);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Opt {
+    CaseInsensitive(bool),
+    MultiLine(bool),
+    DotMatchesNewLine(bool),
+    Crlf(bool),
+    #[cfg(feature = "regex")]
+    LineTerminator(u8),
+    SwapGreed(bool),
+    IgnoreWhitespace(bool),
+    #[cfg(feature = "regex")]
+    Unicode(bool),
+    #[cfg(feature = "regex")]
+    Octal(bool),
+    SizeLimit(usize),
+    #[cfg(feature = "regex")]
+    DfaSizeLimit(usize),
+    NestLimit(u32),
+    #[cfg(feature = "fancy-regex")]
+    BacktrackLimit(usize),
+}
+
+impl Options {
+    /// No options, leaving every setting at its default.
+    pub fn new() -> Self {
+        Options::default()
+    }
+
+    /// Whether no options are set.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// See `RegexBuilder::case_insensitive`.
+    pub fn case_insensitive(self, yes: bool) -> Self {
+        self.with(Opt::CaseInsensitive(yes))
+    }
+
+    /// See `RegexBuilder::multi_line`.
+    pub fn multi_line(self, yes: bool) -> Self {
+        self.with(Opt::MultiLine(yes))
+    }
+
+    /// See `RegexBuilder::dot_matches_new_line`.
+    pub fn dot_matches_new_line(self, yes: bool) -> Self {
+        self.with(Opt::DotMatchesNewLine(yes))
+    }
+
+    /// See `RegexBuilder::crlf`.
+    pub fn crlf(self, yes: bool) -> Self {
+        self.with(Opt::Crlf(yes))
+    }
+
+    /// See `RegexBuilder::line_terminator`.
+    #[cfg(feature = "regex")]
+    pub fn line_terminator(self, byte: u8) -> Self {
+        self.with(Opt::LineTerminator(byte))
+    }
+
+    /// See `RegexBuilder::swap_greed`.
+    pub fn swap_greed(self, yes: bool) -> Self {
+        self.with(Opt::SwapGreed(yes))
+    }
+
+    /// See `RegexBuilder::ignore_whitespace`.
+    pub fn ignore_whitespace(self, yes: bool) -> Self {
+        self.with(Opt::IgnoreWhitespace(yes))
+    }
+
+    /// See `RegexBuilder::unicode`.
+    #[cfg(feature = "regex")]
+    pub fn unicode(self, yes: bool) -> Self {
+        self.with(Opt::Unicode(yes))
+    }
+
+    /// See `RegexBuilder::octal`.
+    #[cfg(feature = "regex")]
+    pub fn octal(self, yes: bool) -> Self {
+        self.with(Opt::Octal(yes))
+    }
+
+    /// See `RegexBuilder::size_limit`.
+    pub fn size_limit(self, bytes: usize) -> Self {
+        self.with(Opt::SizeLimit(bytes))
+    }
+
+    /// See `RegexBuilder::dfa_size_limit`.
+    #[cfg(feature = "regex")]
+    pub fn dfa_size_limit(self, bytes: usize) -> Self {
+        self.with(Opt::DfaSizeLimit(bytes))
+    }
+
+    /// See `RegexBuilder::nest_limit`.
+    pub fn nest_limit(self, limit: u32) -> Self {
+        self.with(Opt::NestLimit(limit))
+    }
+
+    /// See `fancy_regex::RegexBuilder::backtrack_limit`. This has no effect
+    /// on the other regex types, which never backtrack.
+    #[cfg(feature = "fancy-regex")]
+    pub fn backtrack_limit(self, limit: usize) -> Self {
+        self.with(Opt::BacktrackLimit(limit))
+    }
+
+    /// Whether whitespace and comments in the pattern are ignored, going by
+    /// the last `ignore_whitespace` option.
+    #[cfg(all(feature = "regex-lite", not(feature = "regex")))]
+    pub(crate) fn ignores_whitespace(&self) -> bool {
+        self.0.iter().rev().find_map(|opt| match *opt {
+            Opt::IgnoreWhitespace(yes) => Some(yes),
+            _ => None,
+        }) == Some(true)
+    }
+
+    fn with(mut self, opt: Opt) -> Self {
+        self.0.push(opt);
+        self
+    }
+
+    /// The syntax settings, for parsing the pattern outside of a builder.
+    #[cfg(feature = "regex")]
+    pub(crate) fn syntax_config(&self) -> syntax::Config {
+        self.0
+            .iter()
+            .fold(syntax::Config::new(), |config, opt| match *opt {
+                Opt::CaseInsensitive(yes) => config.case_insensitive(yes),
+                Opt::MultiLine(yes) => config.multi_line(yes),
+                Opt::DotMatchesNewLine(yes) => config.dot_matches_new_line(yes),
+                Opt::Crlf(yes) => config.crlf(yes),
+                Opt::LineTerminator(byte) => config.line_terminator(byte),
+                Opt::SwapGreed(yes) => config.swap_greed(yes),
+                Opt::IgnoreWhitespace(yes) => config.ignore_whitespace(yes),
+                Opt::Unicode(yes) => config.unicode(yes),
+                Opt::Octal(yes) => config.octal(yes),
+                Opt::NestLimit(limit) => config.nest_limit(limit),
+                Opt::SizeLimit(_) | Opt::DfaSizeLimit(_) => config,
+                #[cfg(feature = "fancy-regex")]
+                Opt::BacktrackLimit(_) => config,
+            })
+    }
+
+    /// The settings that are not about syntax.
+    #[cfg(feature = "regex")]
+    pub(crate) fn meta_config(&self) -> meta::Config {
+        self.0
+            .iter()
+            .fold(meta::Config::new(), |config, opt| match *opt {
+                Opt::SizeLimit(bytes) => config.nfa_size_limit(Some(bytes)),
+                Opt::DfaSizeLimit(bytes) => config.hybrid_cache_capacity(bytes),
+                _ => config,
+            })
+    }
+}
+
+/// Applies `Options` to the `RegexBuilder` of either `Regex` or
+/// `regex::bytes::Regex`, which have the same methods but no common trait.
+macro_rules! configure {
+    ($builder:ident, $options:expr) => {
+        for opt in &$options.0 {
+            match *opt {
+                Opt::CaseInsensitive(yes) => $builder.case_insensitive(yes),
+                Opt::MultiLine(yes) => $builder.multi_line(yes),
+                Opt::DotMatchesNewLine(yes) => $builder.dot_matches_new_line(yes),
+                Opt::Crlf(yes) => $builder.crlf(yes),
+                #[cfg(feature = "regex")]
+                Opt::LineTerminator(byte) => $builder.line_terminator(byte),
+                Opt::SwapGreed(yes) => $builder.swap_greed(yes),
+                Opt::IgnoreWhitespace(yes) => $builder.ignore_whitespace(yes),
+                #[cfg(feature = "regex")]
+                Opt::Unicode(yes) => $builder.unicode(yes),
+                #[cfg(feature = "regex")]
+                Opt::Octal(yes) => $builder.octal(yes),
+                Opt::SizeLimit(bytes) => $builder.size_limit(bytes),
+                #[cfg(feature = "regex")]
+                Opt::DfaSizeLimit(bytes) => $builder.dfa_size_limit(bytes),
+                Opt::NestLimit(limit) => $builder.nest_limit(limit),
+                #[cfg(feature = "fancy-regex")]
+                Opt::BacktrackLimit(_) => &mut $builder,
+            };
+        }
+    };
+}
+
+impl Options {
+    /// Builds `pattern` into a `Regex` with these options.
+    pub(crate) fn build(&self, pattern: &str) -> Result<Regex, Error> {
+        let mut builder = RegexBuilder::new(pattern);
+        configure!(builder, self);
+        builder.build()
+    }
+
+    /// Builds `pattern` into a `regex::bytes::Regex` with these options.
+    #[cfg(feature = "regex")]
+    pub(crate) fn build_bytes(&self, pattern: &str) -> Result<regex::bytes::Regex, Error> {
+        let mut builder = regex::bytes::RegexBuilder::new(pattern);
+        configure!(builder, self);
+        builder.build()
+    }
+
+    /// Builds `pattern` into a `fancy_regex::Regex` with these options, which
+    /// fails if fancy-regex has no equivalent for one of them.
+    #[cfg(feature = "fancy-regex")]
+    pub(crate) fn build_fancy(&self, pattern: &str) -> Result<fancy_regex::Regex, CompileError> {
+        let mut builder = fancy_regex::RegexBuilder::new(pattern);
+        for opt in &self.0 {
+            match *opt {
+                Opt::CaseInsensitive(yes) => builder.case_insensitive(yes),
+                Opt::MultiLine(yes) => builder.multi_line(yes),
+                Opt::DotMatchesNewLine(yes) => builder.dot_matches_new_line(yes),
+                Opt::Crlf(yes) => builder.crlf(yes),
+                Opt::IgnoreWhitespace(yes) => builder.ignore_whitespace(yes),
+                #[cfg(feature = "regex")]
+                Opt::Unicode(yes) => builder.unicode_mode(yes),
+                Opt::SizeLimit(bytes) => builder.delegate_size_limit(bytes),
+                #[cfg(feature = "regex")]
+                Opt::DfaSizeLimit(bytes) => builder.delegate_dfa_size_limit(bytes),
+                Opt::BacktrackLimit(limit) => builder.backtrack_limit(limit),
+                #[cfg(feature = "regex")]
+                Opt::LineTerminator(_) => return Err(CompileError::Unsupported("line_terminator")),
+                Opt::SwapGreed(_) => return Err(CompileError::Unsupported("swap_greed")),
+                #[cfg(feature = "regex")]
+                Opt::Octal(_) => return Err(CompileError::Unsupported("octal")),
+                Opt::NestLimit(_) => return Err(CompileError::Unsupported("nest_limit")),
+            };
+        }
+        builder.build().map_err(CompileError::Fancy)
+    }
+}
+
+/// Lists the options as they would be written in the macro, with `true`
+/// flags written as just their name.
+impl fmt::Display for Options {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, opt) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            let (name, value) = match *opt {
+                Opt::CaseInsensitive(yes) => ("case_insensitive", flag(yes)),
+                Opt::MultiLine(yes) => ("multi_line", flag(yes)),
+                Opt::DotMatchesNewLine(yes) => ("dot_matches_new_line", flag(yes)),
+                Opt::Crlf(yes) => ("crlf", flag(yes)),
+                #[cfg(feature = "regex")]
+                Opt::LineTerminator(byte) => (
+                    "line_terminator",
+                    Some(format!("b'{}'", byte.escape_ascii())),
+                ),
+                Opt::SwapGreed(yes) => ("swap_greed", flag(yes)),
+                Opt::IgnoreWhitespace(yes) => ("ignore_whitespace", flag(yes)),
+                #[cfg(feature = "regex")]
+                Opt::Unicode(yes) => ("unicode", flag(yes)),
+                #[cfg(feature = "regex")]
+                Opt::Octal(yes) => ("octal", flag(yes)),
+                Opt::SizeLimit(bytes) => ("size_limit", Some(bytes.to_string())),
+                #[cfg(feature = "regex")]
+                Opt::DfaSizeLimit(bytes) => ("dfa_size_limit", Some(bytes.to_string())),
+                Opt::NestLimit(limit) => ("nest_limit", Some(limit.to_string())),
+                #[cfg(feature = "fancy-regex")]
+                Opt::BacktrackLimit(limit) => ("backtrack_limit", Some(limit.to_string())),
+            };
+            match value {
+                Some(value) => write!(f, "{name}({value})")?,
+                None => f.write_str(name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn flag(yes: bool) -> Option<String> {
+    if yes {
+        None
+    } else {
+        Some("false".to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Options;
+
+    #[test]
+    #[cfg(feature = "fancy-regex")]
+    fn build_fancy() {
+        let options = Options::new().case_insensitive(true).backtrack_limit(10);
+        let re = options.build_fancy("^hello(?= world)").unwrap();
+        assert!(re.is_match("HELLO world").unwrap());
+        assert_eq!(options.to_string(), "case_insensitive, backtrack_limit(10)");
+        let err = Options::new()
+            .swap_greed(true)
+            .build_fancy("a+")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the `swap_greed` option is not supported by fancy-regex",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn display() {
+        assert_eq!(Options::new().to_string(), "");
+        let options = Options::new()
+            .case_insensitive(true)
+            .unicode(false)
+            .line_terminator(b'\0')
+            .size_limit(1 << 20);
+        assert_eq!(
+            options.to_string(),
+            r"case_insensitive, unicode(false), line_terminator(b'\x00'), size_limit(1048576)",
+        );
+    }
+
+    #[test]
+    fn build() {
+        let options = Options::new().case_insensitive(true).multi_line(true);
+        let re = options.build("^hello$").unwrap();
+        assert!(re.is_match("x\nHELLO\ny"));
+        let err = Options::new().size_limit(10).build(r"\w{100}").unwrap_err();
+        #[cfg(feature = "regex")]
+        assert!(matches!(err, regex::Error::CompiledTooBig(10)));
+        #[cfg(not(feature = "regex"))]
+        assert_eq!(err.to_string(), "compiled regex exceeded size limit");
+    }
+}
@@ -0,0 +1,343 @@
+//! Explains how far a regex got before it failed to match.
+
+use crate::options::Options;
+#[cfg(feature = "regex")]
+use regex_automata::meta;
+#[cfg(feature = "regex")]
+use regex_syntax::ast::{self, Ast};
+#[cfg(feature = "regex")]
+use regex_syntax::hir::translate::TranslatorBuilder;
+use std::ops::Range;
+
+/// The longest prefix of a regex that matches somewhere in the haystack.
+pub(crate) struct PartialMatch<'a> {
+    /// The part of the pattern that matched.
+    pub(crate) pattern: &'a str,
+    /// The byte range of the haystack that the pattern prefix matched.
+    pub(crate) range: Range<usize>,
+}
+
+impl<'a> PartialMatch<'a> {
+    /// Finds the longest prefix of the top-level concatenation in `pattern`
+    /// that still matches `haystack`. Returns `None` if the pattern is not a
+    /// concatenation, if no proper prefix of it matches, or if the longest
+    /// one only matches the empty string.
+    #[cfg(feature = "regex")]
+    pub(crate) fn find(haystack: &str, pattern: &'a str, options: &Options) -> Option<Self> {
+        let config = options.syntax_config();
+        let ast = ast::parse::ParserBuilder::new()
+            .ignore_whitespace(config.get_ignore_whitespace())
+            .octal(config.get_octal())
+            .nest_limit(config.get_nest_limit())
+            .build()
+            .parse(pattern)
+            .ok()?;
+        let mut translator = TranslatorBuilder::new()
+            .case_insensitive(config.get_case_insensitive())
+            .multi_line(config.get_multi_line())
+            .dot_matches_new_line(config.get_dot_matches_new_line())
+            .crlf(config.get_crlf())
+            .line_terminator(config.get_line_terminator())
+            .swap_greed(config.get_swap_greed())
+            .unicode(config.get_unicode())
+            .build();
+        let concat = match &ast {
+            Ast::Concat(concat) => concat,
+            _ => return None,
+        };
+        let (len, range) = longest_prefix(concat.asts.len(), |len| {
+            let prefix = Ast::concat(ast::Concat {
+                span: concat.span,
+                asts: concat.asts[..len].to_vec(),
+            });
+            let hir = translator.translate(pattern, &prefix).ok()?;
+            let re = meta::Regex::builder().build_from_hir(&hir).ok()?;
+            Some(re.find(haystack)?.range())
+        })?;
+        if range.is_empty() {
+            return None;
+        }
+        Some(PartialMatch {
+            pattern: &pattern[..concat.asts[len - 1].span().end.offset],
+            range,
+        })
+    }
+
+    /// Finds the longest prefix of the top-level concatenation in `pattern`
+    /// that still matches `haystack`, like the `regex` version does.
+    ///
+    /// regex-lite has no parser to split the pattern with, so this splits it
+    /// with [`concat_ends`], and compiles each prefix from the pattern text.
+    #[cfg(all(feature = "regex-lite", not(feature = "regex")))]
+    pub(crate) fn find(haystack: &str, pattern: &'a str, options: &Options) -> Option<Self> {
+        let ends = concat_ends(pattern, options.ignores_whitespace())?;
+        let (len, range) = longest_prefix(ends.len(), |len| {
+            Some(
+                options
+                    .build(&pattern[..ends[len - 1]])
+                    .ok()?
+                    .find(haystack)?
+                    .range(),
+            )
+        })?;
+        if range.is_empty() {
+            return None;
+        }
+        Some(PartialMatch {
+            pattern: &pattern[..ends[len - 1]],
+            range,
+        })
+    }
+}
+
+/// Splits a valid `pattern` into the items of its top-level concatenation, the
+/// way that `regex-syntax` does, and returns where each of them ends. An item
+/// is a literal character, an escape, a class, a group, or a flag setting such
+/// as `(?i)`, along with any repetition operator after it. Returns `None` if
+/// the top level is an alternation.
+///
+/// `verbose` is whether whitespace and comments are ignored to begin with,
+/// which `(?x)` and `(?-x)` at the top level change.
+#[cfg(all(feature = "regex-lite", not(feature = "regex")))]
+fn concat_ends(pattern: &str, mut verbose: bool) -> Option<Vec<usize>> {
+    let mut ends = Vec::new();
+    let mut i = 0;
+    while let Some(c) = pattern[i..].chars().next() {
+        i = match c {
+            _ if verbose && c.is_whitespace() => {
+                i += c.len_utf8();
+                continue;
+            }
+            '#' if verbose => {
+                i = comment_end(pattern, i);
+                continue;
+            }
+            '|' => return None,
+            '\\' => escape_end(pattern, i),
+            '[' => class_end(pattern, i)?,
+            '(' => {
+                let end = group_end(pattern, i, verbose)?;
+                if let Some(flags) = pattern[i..end].strip_prefix("(?") {
+                    if !flags.contains(':') {
+                        // A `-` turns off the flags after it.
+                        if let Some(x) = flags.find('x') {
+                            verbose = !flags[..x].contains('-');
+                        }
+                    }
+                }
+                end
+            }
+            _ => i + c.len_utf8(),
+        };
+        i = repetition_end(pattern, i, verbose);
+        ends.push(i);
+    }
+    Some(ends)
+}
+
+/// Where the comment that starts at `i` ends, after its newline.
+#[cfg(all(feature = "regex-lite", not(feature = "regex")))]
+fn comment_end(pattern: &str, i: usize) -> usize {
+    pattern[i..].find('\n').map_or(pattern.len(), |n| i + n + 1)
+}
+
+/// Where the escape that starts with the `\` at `i` ends, such as `\d`,
+/// `\x7F`, or `\p{Greek}`.
+#[cfg(all(feature = "regex-lite", not(feature = "regex")))]
+fn escape_end(pattern: &str, i: usize) -> usize {
+    let mut chars = pattern[i + 1..].chars();
+    let Some(c) = chars.next() else {
+        return pattern.len();
+    };
+    let end = i + 1 + c.len_utf8();
+    if pattern[end..].starts_with('{') {
+        return pattern[end..]
+            .find('}')
+            .map_or(pattern.len(), |n| end + n + 1);
+    }
+    let digits = match c {
+        'x' => 2,
+        'u' => 4,
+        'U' => 8,
+        _ => 0,
+    };
+    end + pattern[end..]
+        .chars()
+        .take(digits)
+        .take_while(char::is_ascii_hexdigit)
+        .count()
+}
+
+/// Where the class that starts with the `[` at `i` ends, after its `]`.
+#[cfg(all(feature = "regex-lite", not(feature = "regex")))]
+fn class_end(pattern: &str, i: usize) -> Option<usize> {
+    let mut j = i + 1;
+    if pattern[j..].starts_with('^') {
+        j += 1;
+    }
+    // A `]` first in a class is part of it.
+    if pattern[j..].starts_with(']') {
+        j += 1;
+    }
+    while let Some(c) = pattern[j..].chars().next() {
+        j = match c {
+            ']' => return Some(j + 1),
+            '\\' => escape_end(pattern, j),
+            '[' if pattern[j..].starts_with("[:") => j + pattern[j..].find(":]")? + 2,
+            '[' => class_end(pattern, j)?,
+            _ => j + c.len_utf8(),
+        };
+    }
+    None
+}
+
+/// Where the group that starts with the `(` at `i` ends, after its `)`.
+#[cfg(all(feature = "regex-lite", not(feature = "regex")))]
+fn group_end(pattern: &str, i: usize, verbose: bool) -> Option<usize> {
+    let mut depth = 0;
+    let mut j = i;
+    while let Some(c) = pattern[j..].chars().next() {
+        j = match c {
+            '(' => {
+                depth += 1;
+                j + 1
+            }
+            ')' if depth == 1 => return Some(j + 1),
+            ')' => {
+                depth -= 1;
+                j + 1
+            }
+            '#' if verbose => comment_end(pattern, j),
+            '\\' => escape_end(pattern, j),
+            '[' => class_end(pattern, j)?,
+            _ => j + c.len_utf8(),
+        };
+    }
+    None
+}
+
+/// Where the repetition operator after the item that ends at `i` ends, along
+/// with a `?` that makes it lazy, or `i` if there is none.
+#[cfg(all(feature = "regex-lite", not(feature = "regex")))]
+fn repetition_end(pattern: &str, i: usize, verbose: bool) -> usize {
+    let skip = |mut j: usize| {
+        if verbose {
+            loop {
+                match pattern[j..].chars().next() {
+                    Some(c) if c.is_whitespace() => j += c.len_utf8(),
+                    Some('#') => j = comment_end(pattern, j),
+                    _ => break,
+                }
+            }
+        }
+        j
+    };
+    let j = skip(i);
+    let end = match pattern[j..].chars().next() {
+        Some('*' | '+' | '?') => j + 1,
+        Some('{') => match pattern[j + 1..].find('}') {
+            Some(n)
+                if pattern[j + 1..j + 1 + n]
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == ',' || c.is_whitespace()) =>
+            {
+                j + n + 2
+            }
+            _ => return i,
+        },
+        _ => return i,
+    };
+    let lazy = skip(end);
+    if pattern[lazy..].starts_with('?') {
+        lazy + 1
+    } else {
+        end
+    }
+}
+
+/// Finds the longest proper prefix of a concatenation of `len` items that
+/// matches, along with where it matched, given `find`, which returns where the
+/// prefix of a given number of items matches, if anywhere.
+///
+/// A match of a prefix also contains a match of every shorter prefix, so this
+/// is a binary search, which compiles only a few prefixes of a long pattern.
+fn longest_prefix(
+    len: usize,
+    mut find: impl FnMut(usize) -> Option<Range<usize>>,
+) -> Option<(usize, Range<usize>)> {
+    // The empty prefix always matches, and the whole pattern never does.
+    let (mut matched, mut unmatched) = (0, len);
+    let mut longest = None;
+    while unmatched - matched > 1 {
+        let mid = matched + (unmatched - matched) / 2;
+        match find(mid) {
+            Some(range) => {
+                matched = mid;
+                longest = Some((mid, range));
+            }
+            None => unmatched = mid,
+        }
+    }
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PartialMatch;
+    use crate::options::Options;
+
+    #[test]
+    fn find() {
+        let partial =
+            PartialMatch::find("version: 1.2.x", r"version: \d+\.\d+\.\d+", &Options::new())
+                .unwrap();
+        assert_eq!(partial.pattern, r"version: \d+\.\d+\.");
+        assert_eq!(partial.range, 0..13);
+
+        let partial = PartialMatch::find("xx foo bar", "foo baz", &Options::new()).unwrap();
+        assert_eq!(partial.pattern, "foo ba");
+        assert_eq!(partial.range, 3..9);
+    }
+
+    #[test]
+    fn find_with_options() {
+        let options = Options::new().case_insensitive(true);
+        let partial = PartialMatch::find("HELLO world", "hello there", &options).unwrap();
+        assert_eq!(partial.pattern, "hello ");
+        assert_eq!(partial.range, 0..6);
+    }
+
+    #[test]
+    fn find_long_literal() {
+        let pattern = "ab".repeat(1500);
+        let partial = PartialMatch::find("xx ababa!", &pattern, &Options::new()).unwrap();
+        assert_eq!(partial.pattern, "ababa");
+        assert_eq!(partial.range, 3..8);
+    }
+
+    #[test]
+    fn find_nothing() {
+        assert!(PartialMatch::find("abc", r"\d", &Options::new()).is_none());
+        assert!(PartialMatch::find("abc", r"x|y", &Options::new()).is_none());
+        assert!(PartialMatch::find("abc", r"xyz", &Options::new()).is_none());
+        assert!(PartialMatch::find("abc", r"x*y", &Options::new()).is_none());
+        assert!(PartialMatch::find("xy!", r"abd|xyz", &Options::new()).is_none());
+    }
+
+    #[test]
+    fn find_items() {
+        let partial =
+            PartialMatch::find("a]b(c)d", r"[]a]+?\]?b(\(c\)|x){1,2}e", &Options::new()).unwrap();
+        assert_eq!(partial.pattern, r"[]a]+?\]?b(\(c\)|x){1,2}");
+        assert_eq!(partial.range, 0..6);
+
+        let options = Options::new().ignore_whitespace(true);
+        let partial = PartialMatch::find("foo baz", "foo # x|(\n \\ bar", &options).unwrap();
+        assert_eq!(partial.pattern, "foo # x|(\n \\ ba");
+        assert_eq!(partial.range, 0..6);
+
+        let partial = PartialMatch::find("ab", "(?x) a b (?-x) c", &Options::new()).unwrap();
+        assert_eq!(partial.pattern, "(?x) a b (?-x)");
+        assert_eq!(partial.range, 0..2);
+    }
+}
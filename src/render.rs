@@ -0,0 +1,511 @@
+//! Renders the haystack in "does not match" panic messages.
+//!
+//! Single-line haystacks are shown in their `Debug` form. Multi-line ones are
+//! shown as numbered lines, since escaping every newline makes them hard to
+//! read. Either way, if part of the regex matched, the text it matched is
+//! highlighted (in color, when `CLICOLOR_FORCE` is set and `NO_COLOR` is not)
+//! and a caret points at where matching stopped.
+//!
+//! Multi-line patterns, such as verbose-mode patterns written as fragments,
+//! are likewise shown line by line rather than with their newlines inline.
+
+use crate::options::Options;
+use crate::partial::PartialMatch;
+use std::ffi::OsStr;
+use std::fmt::{self, Write as _};
+use std::ops::Range;
+
+/// How many escaped characters of a single-line haystack to show on either
+/// side of the caret before eliding the rest.
+const CONTEXT: usize = 40;
+
+const GREEN: &str = "\x1b[32m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const RESET: &str = "\x1b[0m";
+
+/// A haystack that a regex failed to match.
+pub struct Mismatch<'a> {
+    haystack: &'a str,
+    name: Option<&'a str>,
+    pattern: &'a str,
+    options: &'a Options,
+    partial: Option<PartialMatch<'a>>,
+    color: bool,
+}
+
+impl<'a> Mismatch<'a> {
+    /// Looks for a partial match of `pattern` to explain the mismatch with,
+    /// using color if the environment asks for it.
+    pub(crate) fn new(haystack: &'a str, pattern: &'a str, options: &'a Options) -> Self {
+        Mismatch::with_color(haystack, pattern, options, use_color())
+    }
+
+    pub(crate) fn with_color(
+        haystack: &'a str,
+        pattern: &'a str,
+        options: &'a Options,
+        color: bool,
+    ) -> Self {
+        Mismatch {
+            haystack,
+            name: None,
+            pattern,
+            options,
+            partial: PartialMatch::find(haystack, pattern, options),
+            color,
+        }
+    }
+
+    /// Like [`Mismatch::new`], but without looking for a partial match, for
+    /// patterns that the regex engine can't parse, such as fancy-regex ones.
+    #[cfg(feature = "fancy-regex")]
+    pub(crate) fn without_partial(
+        haystack: &'a str,
+        pattern: &'a str,
+        options: &'a Options,
+    ) -> Self {
+        Mismatch {
+            haystack,
+            name: None,
+            pattern,
+            options,
+            partial: None,
+            color: use_color(),
+        }
+    }
+
+    /// Refers to the haystack as `name` in the summary, and always shows it in
+    /// the details, for haystacks that were made from some other value.
+    pub(crate) fn named(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// The first line of the panic message, after "assertion failed: ".
+    pub fn summary(&self) -> Summary<'_> {
+        Summary(self)
+    }
+
+    /// The rest of the panic message, after any user-provided message.
+    pub fn details(&self) -> Details<'_> {
+        Details(self)
+    }
+}
+
+pub struct Summary<'a>(&'a Mismatch<'a>);
+
+impl fmt::Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let m = self.0;
+        match m.name {
+            Some(name) => f.write_str(name)?,
+            None => write!(f, "{}", HaystackName(m.haystack))?,
+        }
+        write!(
+            f,
+            " does not match {}{}",
+            Pattern(m.pattern),
+            WithOptions(m.options),
+        )
+    }
+}
+
+pub struct Details<'a>(&'a Mismatch<'a>);
+
+impl fmt::Display for Details<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let m = self.0;
+        write!(f, "{}", PatternLines(m.pattern))?;
+        match &m.partial {
+            Some(partial) if is_multi_line(partial.pattern) => write!(
+                f,
+                "\nthe longest matching prefix of the regex ends with `{}`, which stops here:",
+                partial.pattern.lines().last().unwrap_or_default().trim(),
+            )?,
+            Some(partial) => write!(
+                f,
+                "\nthe longest matching prefix of the regex is `{}`, which stops here:",
+                partial.pattern,
+            )?,
+            None => {}
+        }
+        let excerpt = Excerpt {
+            haystack: m.haystack,
+            range: m.partial.as_ref().map(|partial| partial.range.clone()),
+            color: m.color,
+        };
+        if m.name.is_some() && !is_multi_line(m.haystack) && m.partial.is_none() {
+            // A named haystack is not in the summary, and the excerpt only
+            // shows a single line if there is a partial match to point at.
+            write!(f, "\n    {:?}", m.haystack)?;
+        }
+        write!(f, "{excerpt}")
+    }
+}
+
+/// Names the haystack in the first line of a panic message: by its `Debug`
+/// form if it is a single line, or as just "haystack" if it is printed as
+/// numbered lines by an [`Excerpt`] that follows.
+pub(crate) struct HaystackName<'a>(pub(crate) &'a str);
+
+impl fmt::Display for HaystackName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if is_multi_line(self.0) {
+            f.write_str("haystack")
+        } else {
+            write!(f, "`{:?}`", self.0)
+        }
+    }
+}
+
+/// The haystack shown below the first line of a panic message, with `range`
+/// highlighted and a caret under its end. A single-line haystack is only
+/// shown if there is a range to point at.
+pub(crate) struct Excerpt<'a> {
+    pub(crate) haystack: &'a str,
+    pub(crate) range: Option<Range<usize>>,
+    pub(crate) color: bool,
+}
+
+impl fmt::Display for Excerpt<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if is_multi_line(self.haystack) {
+            write_lines(f, self.haystack, self.range.clone(), self.color)
+        } else if let Some(range) = &self.range {
+            write_excerpt(f, self.haystack, range.clone(), self.color)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Names the regex in the first line of a panic message: by its pattern if it
+/// is a single line, or as just "the regex" if it is printed line by line by
+/// [`PatternLines`] further down.
+pub struct Pattern<'a>(pub &'a str);
+
+impl fmt::Display for Pattern<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if is_multi_line(self.0) {
+            f.write_str("the regex")
+        } else {
+            write!(f, "`{}`", self.0)
+        }
+    }
+}
+
+/// Echoes the options that a regex was compiled with, if any, after its
+/// pattern.
+pub struct WithOptions<'a>(pub &'a Options);
+
+impl fmt::Display for WithOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            write!(f, " with options `{}`", self.0)
+        }
+    }
+}
+
+/// A multi-line pattern as it was written, indented on the lines below the
+/// first line of a panic message. Blank lines at either end are left out.
+/// Single-line patterns are already shown by [`Pattern`], so they are not
+/// repeated.
+pub struct PatternLines<'a>(pub &'a str);
+
+impl fmt::Display for PatternLines<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !is_multi_line(self.0) {
+            return Ok(());
+        }
+        let lines = self.0.trim_end().lines();
+        for line in lines.skip_while(|line| line.trim().is_empty()) {
+            write!(f, "\n    {line}")?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn is_multi_line(s: &str) -> bool {
+    s.contains('\n')
+}
+
+/// Formats a byte string like a `b"..."` literal.
+pub struct BytesDisplay<'a>(pub &'a [u8]);
+
+impl fmt::Display for BytesDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "b\"{}\"", self.0.escape_ascii())
+    }
+}
+
+/// Whether to use ANSI colors. They end up in the panic payload, which tests
+/// may match on, so they are only used when asked for with `CLICOLOR_FORCE`,
+/// and never when `NO_COLOR` is set, whether or not stderr is a terminal.
+pub(crate) fn use_color() -> bool {
+    wants_color(
+        std::env::var_os("NO_COLOR").as_deref(),
+        std::env::var_os("CLICOLOR_FORCE").as_deref(),
+    )
+}
+
+/// Whether the values of `NO_COLOR` and `CLICOLOR_FORCE` ask for color,
+/// following <https://no-color.org> and <https://bixense.com/clicolors/>.
+fn wants_color(no_color: Option<&OsStr>, clicolor_force: Option<&OsStr>) -> bool {
+    no_color.is_none_or(OsStr::is_empty)
+        && clicolor_force.is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Writes the `Debug` form of a single-line haystack, with `range` highlighted
+/// and a caret under its end.
+fn write_excerpt(
+    f: &mut fmt::Formatter<'_>,
+    haystack: &str,
+    range: Range<usize>,
+    color: bool,
+) -> fmt::Result {
+    let escaped: Vec<char> = format!("{haystack:?}").chars().collect();
+    // The escaped prefix ends with a closing quote where the rest of the
+    // haystack would begin.
+    let column = |offset: usize| format!("{:?}", &haystack[..offset]).chars().count() - 1;
+    let highlight = column(range.start)..column(range.end);
+    let start = highlight.end.saturating_sub(CONTEXT);
+    let end = escaped.len().min(highlight.end + CONTEXT);
+    let mut indent = highlight.end - start;
+    f.write_str("\n    ")?;
+    if start > 0 {
+        f.write_str("...")?;
+        indent += 3;
+    }
+    write_highlighted(f, &escaped[start..end], sub(&highlight, start), color)?;
+    if end < escaped.len() {
+        f.write_str("...")?;
+    }
+    write_caret(f, "\n    ", indent, color)
+}
+
+/// Writes a multi-line haystack as numbered lines, with `range` highlighted
+/// and a caret under the line where it ends.
+fn write_lines(
+    f: &mut fmt::Formatter<'_>,
+    haystack: &str,
+    range: Option<Range<usize>>,
+    color: bool,
+) -> fmt::Result {
+    let width = (haystack.matches('\n').count() + 1).to_string().len();
+    let mut caret_done = false;
+    let mut line_start = 0;
+    for (number, line) in haystack.split('\n').enumerate() {
+        let line_end = line_start + line.len();
+        let escaped: Vec<char> = escape_line(line).chars().collect();
+        let column = |offset: usize| escape_line(&line[..offset - line_start]).chars().count();
+        let highlight = match &range {
+            Some(range) if range.start <= line_end && range.end >= line_start => {
+                column(range.start.max(line_start))..column(range.end.min(line_end))
+            }
+            _ => 0..0,
+        };
+        write!(f, "\n{:>width$} | ", number + 1)?;
+        write_highlighted(f, &escaped, highlight, color)?;
+        if let Some(range) = &range {
+            if !caret_done && range.end <= line_end {
+                write_caret(f, &format!("\n{:width$} | ", ""), column(range.end), color)?;
+                caret_done = true;
+            }
+        }
+        line_start = line_end + 1;
+    }
+    Ok(())
+}
+
+/// Some lines of a haystack, each shown with its 1-based line number.
+pub(crate) struct NumberedLines<'a>(pub(crate) Vec<(usize, &'a str)>);
+
+impl fmt::Display for NumberedLines<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.0.last().map_or(0, |(n, _)| n.to_string().len());
+        for (number, line) in &self.0 {
+            write!(f, "\n{number:>width$} | {}", escape_line(line))?;
+        }
+        Ok(())
+    }
+}
+
+/// Escapes control characters, so that every character in a line takes up
+/// one or more columns and none of them move the cursor.
+fn escape_line(line: &str) -> String {
+    let mut escaped = String::with_capacity(line.len());
+    for c in line.chars() {
+        if c.is_control() {
+            write!(escaped, "{}", c.escape_debug()).unwrap();
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+fn write_highlighted(
+    f: &mut fmt::Formatter<'_>,
+    chars: &[char],
+    highlight: Range<usize>,
+    color: bool,
+) -> fmt::Result {
+    let highlight = highlight.start.min(chars.len())..highlight.end.min(chars.len());
+    for (i, c) in chars.iter().enumerate() {
+        if color && i == highlight.start && !highlight.is_empty() {
+            f.write_str(GREEN)?;
+        }
+        f.write_char(*c)?;
+        if color && i + 1 == highlight.end && !highlight.is_empty() {
+            f.write_str(RESET)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_caret(
+    f: &mut fmt::Formatter<'_>,
+    prefix: &str,
+    indent: usize,
+    color: bool,
+) -> fmt::Result {
+    if color {
+        write!(f, "{prefix}{:indent$}{BOLD_RED}^{RESET}", "")
+    } else {
+        write!(f, "{prefix}{:indent$}^", "")
+    }
+}
+
+/// Shifts `range` left by `offset`, saturating at zero.
+fn sub(range: &Range<usize>, offset: usize) -> Range<usize> {
+    range.start.saturating_sub(offset)..range.end.saturating_sub(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mismatch;
+    use crate::options::Options;
+    use std::ffi::OsStr;
+
+    fn render(haystack: &str, pattern: &str, color: bool) -> String {
+        let options = Options::new();
+        let m = Mismatch::with_color(haystack, pattern, &options, color);
+        format!("{}{}", m.summary(), m.details())
+    }
+
+    #[test]
+    fn single_line() {
+        assert_eq!(
+            render("abc", r"\d", false),
+            r#"`"abc"` does not match `\d`"#
+        );
+        assert_eq!(
+            render("a\tb c", r"a\tb d", false),
+            "`\"a\\tb c\"` does not match `a\\tb d`\n\
+             the longest matching prefix of the regex is `a\\tb `, which stops here:\n    \
+             \"a\\tb c\"\n          ^",
+        );
+    }
+
+    #[test]
+    fn single_line_long() {
+        let haystack = format!("{}key=value{}", "x".repeat(100), "y".repeat(100));
+        let details = format!(
+            "\nthe longest matching prefix of the regex is `key=`, which stops here:\n    \
+             ...{}key={}...\n    {}^",
+            "x".repeat(36),
+            "value".to_owned() + &"y".repeat(35),
+            " ".repeat(3 + 40),
+        );
+        let options = Options::new();
+        let m = Mismatch::with_color(&haystack, "key=VALUE", &options, false);
+        assert_eq!(m.details().to_string(), details);
+    }
+
+    #[test]
+    fn wants_color() {
+        let yes = Some(OsStr::new("1"));
+        assert!(!super::wants_color(None, None));
+        assert!(super::wants_color(None, yes));
+        assert!(super::wants_color(Some(OsStr::new("")), yes));
+        assert!(!super::wants_color(yes, yes));
+        assert!(!super::wants_color(None, Some(OsStr::new("0"))));
+        assert!(!super::wants_color(None, Some(OsStr::new(""))));
+    }
+
+    #[test]
+    fn single_line_color() {
+        assert_eq!(
+            render("foo bar", "foo baz", true),
+            "`\"foo bar\"` does not match `foo baz`\n\
+             the longest matching prefix of the regex is `foo ba`, which stops here:\n    \
+             \"\x1b[32mfoo ba\x1b[0mr\"\n           \x1b[1;31m^\x1b[0m",
+        );
+    }
+
+    #[test]
+    fn multi_line() {
+        assert_eq!(
+            render("one\ntwo\n", r"\d", false),
+            "haystack does not match `\\d`\n1 | one\n2 | two\n3 | ",
+        );
+        assert_eq!(
+            render("started\r\nstatus: ok\nstopped", r"status: error", false),
+            "haystack does not match `status: error`\n\
+             the longest matching prefix of the regex is `status: `, which stops here:\n\
+             1 | started\\r\n\
+             2 | status: ok\n  \
+             |         ^\n\
+             3 | stopped",
+        );
+    }
+
+    #[test]
+    fn multi_line_color() {
+        assert_eq!(
+            render("a\nbc\nd", r"c\nd!", true),
+            "haystack does not match `c\\nd!`\n\
+             the longest matching prefix of the regex is `c\\nd`, which stops here:\n\
+             1 | a\n\
+             2 | b\x1b[32mc\x1b[0m\n\
+             3 | \x1b[32md\x1b[0m\n  \
+             |  \x1b[1;31m^\x1b[0m",
+        );
+    }
+
+    #[test]
+    fn multi_line_pattern() {
+        assert_eq!(
+            render(
+                "key = value",
+                "(?x)\n(?<key>\\w+)  # the key\n\\s*=\\s*\n(?<value>\\d+)",
+                false,
+            ),
+            "`\"key = value\"` does not match the regex\n    \
+             (?x)\n    \
+             (?<key>\\w+)  # the key\n    \
+             \\s*=\\s*\n    \
+             (?<value>\\d+)\n\
+             the longest matching prefix of the regex ends with `\\s*=\\s*`, which stops here:\n    \
+             \"key = value\"\n           ^",
+        );
+        assert_eq!(
+            render("abc", "\n    (?x) \\d\n    \\d\n", false),
+            "`\"abc\"` does not match the regex\n        (?x) \\d\n        \\d",
+        );
+    }
+
+    #[test]
+    fn options() {
+        let options = Options::new().case_insensitive(true).crlf(true);
+        let m = Mismatch::with_color("FOO bar", "foo baz", &options, false);
+        assert_eq!(
+            format!("{}{}", m.summary(), m.details()),
+            "`\"FOO bar\"` does not match `foo baz` with options `case_insensitive, crlf`\n\
+             the longest matching prefix of the regex is `foo ba`, which stops here:\n    \
+             \"FOO bar\"\n           ^",
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Asserting that several regexes match one after another.
+
+use crate::backend::Regex;
+use crate::check::invalid_regex;
+use crate::compile::Compiled;
+use crate::haystack::Text;
+use crate::render::{self, Excerpt, HaystackName};
+use std::fmt;
+
+/// Panics unless each regex in `res` matches `haystack` somewhere after the
+/// end of the previous regex's match.
+#[track_caller]
+pub fn assert_in_order(
+    haystack: Text<'_>,
+    res: &[&Compiled<Regex>],
+    args: Option<fmt::Arguments<'_>>,
+) {
+    let note = haystack.note();
+    let haystack = haystack.as_str();
+    for re in res {
+        if re.result().is_err() {
+            invalid_regex("assert_matches_in_order", re, args);
+        }
+    }
+    let mut previous = None;
+    let mut start = 0;
+    for (i, re) in res.iter().enumerate() {
+        if let Some(m) = re.get().find_at(haystack, start) {
+            previous = Some((i, m.range()));
+            start = m.end();
+            continue;
+        }
+        let mut summary = format!(
+            "{} does not match `{}` (pattern {} of {})",
+            HaystackName(haystack),
+            re.pattern(),
+            i + 1,
+            res.len(),
+        );
+        if let Some((j, _)) = &previous {
+            summary += &format!(
+                " after byte {start}, where `{}` (pattern {}) ended",
+                res[*j].pattern(),
+                j + 1,
+            );
+        }
+        let excerpt = Excerpt {
+            haystack,
+            range: previous.map(|(_, range)| range),
+            color: render::use_color(),
+        };
+        match args {
+            Some(args) => panic!("assertion failed: {summary}: {args}{excerpt}{note}"),
+            None => panic!("assertion failed: {summary}{excerpt}{note}"),
+        }
+    }
+}
+
+/// Asserts that a list of regexes match a string in order, using
+/// [`regex::Regex`].
+///
+/// Each regex must match somewhere after the end of the previous regex's
+/// match, which is useful for checking that events in a log happened in a
+/// given order. On failure, the panic message says which regex in the list
+/// failed and where its search resumed from, and highlights the previous
+/// match.
+///
+/// [`regex::Regex`]: https://docs.rs/regex/*/regex/struct.Regex.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_matches_in_order;
+/// let log = "server started\nclient connected\nserver shutdown\n";
+/// assert_matches_in_order!(log, ["started", "connected", "shutdown"]);
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_matches_in_order;
+/// let log = "client connected\nserver started\n";
+/// assert_matches_in_order!(log, ["started", "connected"], "connected too early");
+/// ```
+#[macro_export]
+macro_rules! assert_matches_in_order {
+    ($haystack:expr, [$($re:expr),+ $(,)?] $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let res = [$($crate::__regex!($re)),+];
+        $crate::__private::assert_in_order(haystack, &res, ::std::option::Option::None);
+    }};
+    ($haystack:expr, [$($re:expr),+ $(,)?], $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let res = [$($crate::__regex!($re)),+];
+        $crate::__private::assert_in_order(
+            haystack,
+            &res,
+            ::std::option::Option::Some(::std::format_args!($($arg)*)),
+        );
+    }};
+}
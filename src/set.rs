@@ -0,0 +1,187 @@
+//! Asserting that several regexes match, in one pass with a `RegexSet`.
+
+use crate::check::panic_invalid_regex;
+use crate::compile::CompiledSet;
+use crate::haystack::Text;
+use crate::options::Options;
+use crate::render::{Excerpt, HaystackName};
+use std::fmt::{self, Write as _};
+
+/// How many of the regexes in a set must match.
+pub enum Patterns {
+    /// Every regex must match.
+    All,
+    /// At least one regex must match.
+    Any,
+}
+
+/// Panics unless the regexes in `set` match `haystack` as `patterns`
+/// requires, listing every regex that did not match.
+#[track_caller]
+pub fn assert_set(
+    haystack: Text<'_>,
+    set: &CompiledSet,
+    patterns: Patterns,
+    args: Option<fmt::Arguments<'_>>,
+) {
+    let note = haystack.note();
+    let haystack = haystack.as_str();
+    let macro_name = match patterns {
+        Patterns::All => "assert_matches_all_regex",
+        Patterns::Any => "assert_matches_any_regex",
+    };
+    let regex_set = match set.result() {
+        Ok(regex_set) => regex_set,
+        Err((pattern, err)) => panic_invalid_regex(macro_name, pattern, &Options::new(), err, args),
+    };
+    let matched = regex_set.matches(haystack);
+    let total = set.patterns().len();
+    let unmatched: Vec<&String> = set
+        .patterns()
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !matched.matched(*i))
+        .map(|(_, pattern)| pattern)
+        .collect();
+    let summary = match patterns {
+        Patterns::All if unmatched.is_empty() => return,
+        Patterns::Any if matched.matched_any() => return,
+        Patterns::All => format!(
+            "{} does not match {} of {total} regexes",
+            HaystackName(haystack),
+            unmatched.len(),
+        ),
+        Patterns::Any => format!(
+            "{} does not match any of {total} regexes",
+            HaystackName(haystack)
+        ),
+    };
+    let mut details = String::new();
+    for pattern in unmatched {
+        write!(details, "\n    `{pattern}`").unwrap();
+    }
+    let excerpt = Excerpt {
+        haystack,
+        range: None,
+        color: false,
+    };
+    match args {
+        Some(args) => panic!("assertion failed: {summary}: {args}{details}{excerpt}{note}"),
+        None => panic!("assertion failed: {summary}{details}{excerpt}{note}"),
+    }
+}
+
+/// Evaluates to a `&CompiledSet` for the patterns. If they are all string
+/// literals, they are validated at compile time and the set is cached per
+/// call site; otherwise it is compiled on every evaluation.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __regex_set {
+    ($($re:literal),+) => {{
+        $($crate::__private::validate_regex!($re);)+
+        static CACHE: $crate::__private::RegexSetCache = $crate::__private::RegexSetCache::new();
+        CACHE.get(&[$($re),+])
+    }};
+    ($($re:expr),+) => {
+        &$crate::__private::CompiledSet::new(&[$($crate::__private::as_pattern(&$re)),+])
+    };
+}
+
+/// Asserts that a string matches every regex in a list, using
+/// [`regex::RegexSet`].
+///
+/// All of the regexes are run against the string in a single pass. On
+/// failure, the panic message lists every regex that did not match, rather
+/// than stopping at the first one.
+///
+/// [`regex::RegexSet`]: https://docs.rs/regex/*/regex/struct.RegexSet.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_matches_all_regex;
+/// let err = "error[E0308]: mismatched types: expected `u32`, found `&str`";
+/// assert_matches_all_regex!(err, [r"^error\[E\d+\]", "mismatched", "expected `u32`"]);
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_matches_all_regex;
+/// let err = "error: mismatched types";
+/// assert_matches_all_regex!(err, ["mismatched", "expected", "found"], "unhelpful error");
+/// ```
+#[macro_export]
+macro_rules! assert_matches_all_regex {
+    ($haystack:expr, [$($re:expr),+ $(,)?] $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let set = $crate::__regex_set!($($re),+);
+        $crate::__private::assert_set(
+            haystack,
+            set,
+            $crate::__private::Patterns::All,
+            ::std::option::Option::None,
+        );
+    }};
+    ($haystack:expr, [$($re:expr),+ $(,)?], $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let set = $crate::__regex_set!($($re),+);
+        $crate::__private::assert_set(
+            haystack,
+            set,
+            $crate::__private::Patterns::All,
+            ::std::option::Option::Some(::std::format_args!($($arg)*)),
+        );
+    }};
+}
+
+/// Asserts that a string matches at least one regex in a list, using
+/// [`regex::RegexSet`].
+///
+/// All of the regexes are run against the string in a single pass. On
+/// failure, the panic message lists every regex.
+///
+/// [`regex::RegexSet`]: https://docs.rs/regex/*/regex/struct.RegexSet.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_matches_any_regex;
+/// let status = "connection reset by peer";
+/// assert_matches_any_regex!(status, ["timed out", "reset", "refused"]);
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_matches_any_regex;
+/// let status = "ok";
+/// assert_matches_any_regex!(status, ["timed out", "reset"], "expected a network error");
+/// ```
+#[macro_export]
+macro_rules! assert_matches_any_regex {
+    ($haystack:expr, [$($re:expr),+ $(,)?] $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let set = $crate::__regex_set!($($re),+);
+        $crate::__private::assert_set(
+            haystack,
+            set,
+            $crate::__private::Patterns::Any,
+            ::std::option::Option::None,
+        );
+    }};
+    ($haystack:expr, [$($re:expr),+ $(,)?], $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let set = $crate::__regex_set!($($re),+);
+        $crate::__private::assert_set(
+            haystack,
+            set,
+            $crate::__private::Patterns::Any,
+            ::std::option::Option::Some(::std::format_args!($($arg)*)),
+        );
+    }};
+}
@@ -0,0 +1,307 @@
+//! Asserting that a string matches a template of literal text and typed
+//! placeholders, by way of a regex.
+
+use crate::backend::Regex;
+use crate::check::assert_translated;
+use crate::compile::Compiled;
+use crate::glob::literal;
+use crate::haystack::Text;
+use crate::options::Options;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::panic::Location;
+use std::sync::{PoisonError, RwLock};
+
+/// The placeholders that every template can use, and the regexes they stand
+/// for.
+const BUILTIN: &[(&str, &str)] = &[
+    ("int", r"[-+]?\d+"),
+    ("float", r"[-+]?(?:\d+(?:\.\d*)?|\.\d+)(?:[eE][-+]?\d+)?"),
+    ("hex", r"(?:0[xX])?[0-9a-fA-F]+"),
+    (
+        "uuid",
+        r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}",
+    ),
+    (
+        "iso8601",
+        r"\d{4}-\d{2}-\d{2}(?:[T ]\d{2}:\d{2}(?::\d{2}(?:[.,]\d+)?)?(?:Z|[-+]\d{2}(?::?\d{2})?)?)?",
+    ),
+    (
+        "semver",
+        r"\d+\.\d+\.\d+(?:-[0-9A-Za-z.\-]+)?(?:\+[0-9A-Za-z.\-]+)?",
+    ),
+    ("path", r"\S+"),
+    ("duration", r"\d+(?:\.\d+)?(?:ns|[µu]s|ms|s|m|h)"),
+    ("word", r"\w+"),
+    ("any", r".*"),
+];
+
+/// The placeholders added with [`register_placeholder`].
+static REGISTERED: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+
+/// Adds a placeholder that templates can use as `{name}`, standing for
+/// `pattern`, for [`assert_matches_template!`] in every test from then on.
+///
+/// A placeholder with the same name, including a built-in one, is replaced.
+/// Since tests run in parallel, a placeholder that several tests use is best
+/// registered by a helper that each of them calls first, rather than by one
+/// test.
+///
+/// [`assert_matches_template!`]: macro.assert_matches_template.html
+///
+/// # Panics
+///
+/// Panics if `pattern` is not a valid regex.
+///
+/// # Examples
+///
+/// ```
+/// use assert_matches_regex::{assert_matches_template, register_placeholder};
+///
+/// register_placeholder("sha", "[0-9a-f]{7,40}");
+/// assert_matches_template!("HEAD is now at 3e1f0a2", "HEAD is now at {sha}");
+/// ```
+#[track_caller]
+pub fn register_placeholder(name: &str, pattern: &str) {
+    if let Err(err) = Regex::new(pattern) {
+        panic!("invalid regex for placeholder `{{{name}}}`: `{pattern}`\n{err}");
+    }
+    REGISTERED
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(name.to_owned(), pattern.to_owned());
+}
+
+/// A template, along with the regex that it was translated into, or the reason
+/// that it could not be.
+pub struct Template {
+    template: String,
+    re: Result<Compiled<Regex>, TemplateError>,
+}
+
+impl Template {
+    /// Translates `template` into a regex and compiles it with `options`.
+    pub fn new(template: &str, options: Options) -> Self {
+        Template {
+            template: template.to_owned(),
+            re: to_regex(template).map(|regex| Compiled::with_options(&regex, options)),
+        }
+    }
+}
+
+/// Why a template could not be translated into a regex.
+#[derive(Debug, PartialEq)]
+pub(crate) enum TemplateError {
+    /// A placeholder that is neither built in nor registered.
+    UnknownPlaceholder(String),
+    /// A `{` with no `}` after it.
+    Unclosed,
+    /// A `}` that does not close a placeholder.
+    Unmatched,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownPlaceholder(name) => write!(
+                f,
+                "unknown placeholder `{{{name}}}`; register it with `register_placeholder`",
+            ),
+            TemplateError::Unclosed => f.write_str("unclosed `{`; write `{{` for a literal one"),
+            TemplateError::Unmatched => f.write_str("unmatched `}`; write `}}` for a literal one"),
+        }
+    }
+}
+
+/// Translates `template` into a regex that matches the same strings: its text
+/// escaped, and each placeholder replaced by its regex in a group. `{{` and
+/// `}}` stand for literal braces. The regex is anchored at both ends.
+pub(crate) fn to_regex(template: &str) -> Result<String, TemplateError> {
+    let registered = REGISTERED.read().unwrap_or_else(PoisonError::into_inner);
+    let mut regex = String::from("^");
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        regex += &literal(&rest[..i]);
+        let brace = &rest[i..i + 1];
+        rest = &rest[i + 1..];
+        if let Some(after) = rest.strip_prefix(brace) {
+            regex += &literal(brace);
+            rest = after;
+            continue;
+        }
+        if brace == "}" {
+            return Err(TemplateError::Unmatched);
+        }
+        let end = rest.find('}').ok_or(TemplateError::Unclosed)?;
+        let name = &rest[..end];
+        let pattern = match registered.get(name) {
+            Some(pattern) => pattern.as_str(),
+            None => BUILTIN
+                .iter()
+                .find(|&&(builtin, _)| builtin == name)
+                .map(|&(_, pattern)| pattern)
+                .ok_or_else(|| TemplateError::UnknownPlaceholder(name.to_owned()))?,
+        };
+        regex += &format!("(?:{pattern})");
+        rest = &rest[end + 1..];
+    }
+    regex += &literal(rest);
+    regex.push('$');
+    Ok(regex)
+}
+
+/// Panics unless `template` matches the whole of `haystack`, showing the regex
+/// that the template was translated into.
+#[track_caller]
+pub fn assert_template(haystack: Text<'_>, template: &Template, args: Option<fmt::Arguments<'_>>) {
+    let re = match &template.re {
+        Ok(re) => re,
+        Err(err) => {
+            let location = Location::caller();
+            let template = &template.template;
+            match args {
+                Some(args) => panic!(
+                    "invalid template in assert_matches_template! at {location}\n\
+                     template: `{template}`\nmessage: {args}\n{err}",
+                ),
+                None => panic!(
+                    "invalid template in assert_matches_template! at {location}\n\
+                     template: `{template}`\n{err}",
+                ),
+            }
+        }
+    };
+    assert_translated(
+        "assert_matches_template",
+        "template",
+        &template.template,
+        re,
+        haystack,
+        args,
+    );
+}
+
+/// Asserts that a string matches a template, such as
+/// `"Finished in {duration} with {int} tests"`, in which only the volatile
+/// parts are patterns.
+///
+/// Text outside braces is matched literally. Each `{name}` is a placeholder
+/// that matches a kind of value, and `{{` and `}}` match a literal brace. The
+/// template must match the whole string. These placeholders are built in:
+///
+/// | Placeholder  | Matches                                                 |
+/// |--------------|---------------------------------------------------------|
+/// | `{int}`      | an integer, such as `42` or `-7`                        |
+/// | `{float}`    | a decimal number, such as `3.14`, `-2`, or `1e-9`       |
+/// | `{hex}`      | hex digits, such as `ff` or `0x1A2b`                    |
+/// | `{uuid}`     | a UUID, such as `67e55044-10b1-426f-9247-bb680e5fe0c8`  |
+/// | `{iso8601}`  | a date, or a date and time, such as `2024-05-01T12:30:00Z` |
+/// | `{semver}`   | a version, such as `1.2.3` or `1.0.0-beta.1+build.5`    |
+/// | `{path}`     | a path without whitespace, such as `src/lib.rs`         |
+/// | `{duration}` | a `Debug`-formatted duration, such as `1.5s` or `250ms` |
+/// | `{word}`     | a run of word characters, such as `foo_bar1`            |
+/// | `{any}`      | anything on one line, including nothing                 |
+///
+/// More can be added with [`register_placeholder`]. The template is translated
+/// into a regex using [`escape`], which the panic message shows along with
+/// the template. Options, such as `case_insensitive`, apply to that regex, and
+/// they and a message can be passed as with [`assert_matches_regex!`].
+///
+/// [`register_placeholder`]: fn.register_placeholder.html
+/// [`escape`]: fn.escape.html
+/// [`assert_matches_regex!`]: macro.assert_matches_regex.html
+///
+/// # Examples
+///
+/// ```
+/// # use assert_matches_regex::assert_matches_template;
+/// assert_matches_template!(
+///     "Finished in 1.52s with 12 tests, id 67e55044-10b1-426f-9247-bb680e5fe0c8",
+///     "Finished in {duration} with {int} tests, id {uuid}",
+/// );
+/// assert_matches_template!("Set { x: 0.5 } at src/main.rs:9", "Set {{ x: {float} }} at {path}:{int}");
+/// ```
+///
+/// On failure, the panic message looks like this:
+///
+/// ```text
+/// assertion failed: `"took 5 ms"` does not match template `took {duration}`
+/// regex: `^took (?:\d+(?:\.\d+)?(?:ns|[µu]s|ms|s|m|h))$`
+/// the longest matching prefix of the regex is `^took `, which stops here:
+///     "took 5 ms"
+///           ^
+/// ```
+///
+/// An optional message in the form of a format string can be passed last.
+///
+/// ```rust,should_panic
+/// # use assert_matches_regex::assert_matches_template;
+/// let status = "v2 ready";
+/// assert_matches_template!(status, "{semver} ready", "bad status `{status}`");
+/// ```
+#[macro_export]
+macro_rules! assert_matches_template {
+    (@options [$($opt:tt)*] $haystack:expr, $template:expr $(,)?) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let template =
+            &$crate::__private::Template::new(&$template, $crate::__private::Options::new() $($opt)*);
+        $crate::__private::assert_template(haystack, template, ::std::option::Option::None);
+    }};
+    (@options [$($opt:tt)*] $haystack:expr, $template:expr, $($arg:tt)+) => {{
+        let haystack = $haystack;
+        $crate::__haystack!(haystack);
+        let template =
+            &$crate::__private::Template::new(&$template, $crate::__private::Options::new() $($opt)*);
+        $crate::__private::assert_template(
+            haystack,
+            template,
+            ::std::option::Option::Some(::std::format_args!($($arg)*)),
+        );
+    }};
+    ($haystack:expr, $template:expr $(, $($rest:tt)*)?) => {
+        $crate::__options!(assert_matches_template [] [$haystack, $template] $($($rest)*)?)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_regex, TemplateError, BUILTIN};
+    use crate::backend::Regex;
+
+    #[test]
+    fn translate() {
+        assert_eq!(to_regex("a.b {int}").unwrap(), r"^a\.b (?:[-+]?\d+)$");
+        assert_eq!(to_regex("{{int}} }}\n").unwrap(), r"^\{int\} \}\n$");
+        assert_eq!(
+            to_regex("{nope}"),
+            Err(TemplateError::UnknownPlaceholder("nope".to_owned())),
+        );
+        assert_eq!(to_regex("a {int"), Err(TemplateError::Unclosed));
+        assert_eq!(to_regex("a } b"), Err(TemplateError::Unmatched));
+    }
+
+    #[test]
+    fn builtin() {
+        let examples = [
+            ("int", "-42"),
+            ("float", "1.5e-3"),
+            ("hex", "0xDEADbeef"),
+            ("uuid", "67e55044-10b1-426f-9247-bb680e5fe0c8"),
+            ("iso8601", "2024-05-01T12:30:00.123+02:00"),
+            ("semver", "1.0.0-beta.1+build.5"),
+            ("path", "C:\\Users\\me\\out.log"),
+            ("duration", "1.25µs"),
+            ("word", "foo_bar1"),
+            ("any", ""),
+        ];
+        assert_eq!(examples.len(), BUILTIN.len());
+        for (name, example) in examples {
+            let regex = Regex::new(&to_regex(&format!("{{{name}}}")).unwrap()).unwrap();
+            assert!(
+                regex.is_match(example),
+                "`{{{name}}}` does not match `{example}`"
+            );
+        }
+    }
+}